@@ -5,12 +5,24 @@ use crate::{error::GameError, ColorGroup, PropertyType};
 // Entry fee and vault constants
 pub const GAME_AUTHORITY_SEED: &[u8] = b"game_authority";
 pub const TOKEN_VAULT_SEED: &[u8] = b"token_vault";
+pub const SOL_VAULT_SEED: &[u8] = b"sol_vault"; // Lamport escrow for games without a mint
+pub const ALLOWED_MINT_SEED: &[u8] = b"allowed_mint"; // SOL games use Pubkey::default() as the mint
+pub const SPONSORSHIP_SEED: &[u8] = b"sponsorship";
+
+// Ephemeral rollup delegation defaults
+pub const DEFAULT_ER_VALIDATOR: Pubkey = pubkey!("MAS1Dt9qreoRMQ14YQuhg8UTZMMzDdKhmkZMECCzk57");
+pub const DEFAULT_COMMIT_FREQUENCY_MS: u32 = 30_000;
+
 pub const WINNER_PERCENTAGE: u16 = 9500; // 95%
 pub const PLATFORM_PERCENTAGE: u16 = 100; // 1%
+pub const TOTAL_BASIS_POINTS: u16 = 10_000; // 100%, payout schedules add up to this
+pub const MAX_FEE_BASIS_POINTS: u16 = 1_000; // Platform fee capped at 10%
+pub const CLAIM_WINDOW_SECONDS: i64 = 30 * 24 * 60 * 60; // 30 days, then unclaimed prizes go to the fee vault
 
 // Game configuration constants
-pub const MAX_PLAYERS: u8 = 4;
+pub const MAX_PLAYERS: u8 = 8;
 pub const MIN_PLAYERS: u8 = 2;
+pub const MAX_PLAYERS_PER_BATCH: usize = 4; // Players delegated or refunded per transaction
 pub const BOARD_SIZE: u8 = 40;
 pub const STARTING_MONEY: u32 = 1500;
 pub const GO_SALARY: u32 = 200;
@@ -38,13 +50,19 @@ pub const TRADE_EXPIRY_SECONDS: i64 = 3600; // 1 hour
 pub const MAX_ACTIVE_TRADES: usize = 20; // Maximum trades stored in GameState vector
 pub const AUCTION_DURATION_SECONDS: i64 = 300; // 5 minutes
 
+// Card deck constants
+pub const CHANCE_DECK_SEED: &[u8] = b"chance_deck";
+pub const COMMUNITY_CHEST_DECK_SEED: &[u8] = b"community_chest_deck";
+pub const MAX_CARDS_PER_DECK: usize = 32;
+pub const MAX_JAIL_CARDS_PER_DECK: usize = 4;
+
 // Special space positions
 pub const GO_POSITION: u8 = 0; // Solana Genesis
 pub const JAIL_POSITION: u8 = 10; // Validator Jail
 pub const GO_TO_JAIL_POSITION: u8 = 30; // Go To Validator Jail
 pub const FREE_PARKING_POSITION: u8 = 20; // Free Airdrop Parking
                                           // pub const BEACH_RESORT_POSITION: u8 = 20; // Replaces Free Parking
-pub const FESTIVAL_POSITION: u8 = 10; // Additional special space
+pub const FESTIVAL_POSITION: u8 = 10; // Just Visiting doubles as the festival square
 
 // Chance and Community Chest positions (now themed)
 pub const CHANCE_POSITIONS: [u8; 3] = [7, 22, 36]; // Pump.fun Surprise
@@ -54,11 +72,14 @@ pub const COMMUNITY_CHEST_POSITIONS: [u8; 3] = [2, 17, 33]; // Airdrop Chest
 pub const DEFAULT_TURN_TIMEOUT_SECONDS: u64 = 30;
 pub const DEFAULT_GRACE_PERIOD_SECONDS: u64 = 10;
 pub const MAX_TIMEOUT_PENALTIES: u8 = 3;
+pub const START_TIMEOUT_SECONDS: i64 = 300; // A start stalled this long can be rolled back and cancelled
 
 
 // Seed for randomness
 pub const RANDOMNESS_SEED: &[u8] = b"panda_monopoly_v1";
 pub const BEACH_RESORT_BONUS_PER_PROPERTY: u32 = 10;
+pub const FESTIVAL_RENT_MULTIPLIER: u64 = 2;
+pub const FESTIVAL_BOOST_TURNS: u8 = 3;
 // pub const FREE_PARKING_POSITION: u8 = 20;
 // pub const JAIL_POSITION: u8 = 10;
 
@@ -562,16 +583,21 @@ pub fn calculate_utility_rent(dice_roll: u8, utilities_owned: u8) -> u64 {
 }
 
 // Card effect types for Solana-themed cards
-#[derive(Clone, Copy, PartialEq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, InitSpace)]
 pub enum CardEffectType {
     Money,
     Move,
     GoToJail,
     GetOutOfJailFree,
-    PayPerProperty,
+    PayPerProperty, // Pay `amount` for every property owned
     CollectFromPlayers,
-    MoveToNearest, // For moving to nearest memecoin/railroad/utility
-    RepairFree,    // For free repairs
+    MoveToNearest,         // For moving to nearest memecoin
+    RepairFree,            // For free repairs
+    PayEachPlayer,         // Pay every other player
+    MoveToNearestRailroad, // Owner collects double rent
+    MoveToNearestUtility,  // Owner collects double rent
+    AdvanceToGo,           // Move to GO and collect the salary
+    StreetRepairs,         // Pay `house_cost` per house and `hotel_cost` per hotel
 }
 
 impl From<CardEffectType> for u8 {
@@ -585,112 +611,253 @@ impl From<CardEffectType> for u8 {
             CardEffectType::CollectFromPlayers => 5,
             CardEffectType::MoveToNearest => 6,
             CardEffectType::RepairFree => 7,
+            CardEffectType::PayEachPlayer => 8,
+            CardEffectType::MoveToNearestRailroad => 9,
+            CardEffectType::MoveToNearestUtility => 10,
+            CardEffectType::AdvanceToGo => 11,
+            CardEffectType::StreetRepairs => 12,
         }
     }
 }
 
-// Chance card structure (Pump.fun Surprise)
-#[derive(Clone)]
-pub struct ChanceCard {
+// Card structure shared by Pump.fun Surprise (Chance) and Airdrop Chest decks
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, InitSpace)]
+pub struct CardData {
     pub id: u8,
     pub effect_type: CardEffectType,
-    pub amount: i32,
-}
-
-// Community Chest card structure (Airdrop Chest)
-#[derive(Clone)]
-pub struct CommunityChestCard {
-    pub id: u8,
-    pub effect_type: CardEffectType,
-    pub amount: i32,
-}
-
-// Festival effect structure
-#[derive(Clone)]
-pub struct FestivalEffect {
-    pub id: u8,
-    pub amount: u32,
-    pub is_positive: bool,
+    pub amount: i32,     // Money, move target or per-property amount
+    pub house_cost: u16, // Street repairs cost per house
+    pub hotel_cost: u16, // Street repairs cost per hotel
 }
 
 // Pump.fun Surprise Cards (Chance cards)
-pub const CHANCE_CARDS: [ChanceCard; 5] = [
-    ChanceCard {
+pub const DEFAULT_CHANCE_CARDS: [CardData; 16] = [
+    CardData {
         id: 1, // "Memecoin Pump!"
         effect_type: CardEffectType::MoveToNearest,
         amount: 1, // Move to nearest memecoin (BONK or WIF)
+        house_cost: 0,
+        hotel_cost: 0,
     },
-    ChanceCard {
+    CardData {
         id: 2, // "Rug Pull Alert!"
         effect_type: CardEffectType::Money,
         amount: -50,
+        house_cost: 0,
+        hotel_cost: 0,
     },
-    ChanceCard {
+    CardData {
         id: 3, // "Flash Loan Win"
         effect_type: CardEffectType::Money,
         amount: 100,
+        house_cost: 0,
+        hotel_cost: 0,
     },
-    ChanceCard {
+    CardData {
         id: 4, // "Congestion Jam"
         effect_type: CardEffectType::Move,
         amount: -3,
+        house_cost: 0,
+        hotel_cost: 0,
     },
-    ChanceCard {
+    CardData {
         id: 5, // "Dev Unlock"
         effect_type: CardEffectType::GetOutOfJailFree,
         amount: 0,
+        house_cost: 0,
+        hotel_cost: 0,
+    },
+    CardData {
+        id: 6, // "Validator Tips"
+        effect_type: CardEffectType::PayEachPlayer,
+        amount: 25, // Pay each player
+        house_cost: 0,
+        hotel_cost: 0,
+    },
+    CardData {
+        id: 7, // "Bridge Hop"
+        effect_type: CardEffectType::MoveToNearestRailroad,
+        amount: 0, // Owner collects double rent
+        house_cost: 0,
+        hotel_cost: 0,
+    },
+    CardData {
+        id: 8, // "Cross-Chain Bridge"
+        effect_type: CardEffectType::MoveToNearestRailroad,
+        amount: 0, // Owner collects double rent
+        house_cost: 0,
+        hotel_cost: 0,
+    },
+    CardData {
+        id: 9, // "Oracle Update"
+        effect_type: CardEffectType::MoveToNearestUtility,
+        amount: 0, // Owner collects double rent
+        house_cost: 0,
+        hotel_cost: 0,
+    },
+    CardData {
+        id: 10, // "Genesis Restart"
+        effect_type: CardEffectType::AdvanceToGo,
+        amount: 0,
+        house_cost: 0,
+        hotel_cost: 0,
+    },
+    CardData {
+        id: 11, // "Slashing Event"
+        effect_type: CardEffectType::GoToJail,
+        amount: 0,
+        house_cost: 0,
+        hotel_cost: 0,
+    },
+    CardData {
+        id: 12, // "Protocol Upgrade"
+        effect_type: CardEffectType::StreetRepairs,
+        amount: 0,
+        house_cost: 25,
+        hotel_cost: 100,
+    },
+    CardData {
+        id: 13, // "Gas Spike"
+        effect_type: CardEffectType::PayPerProperty,
+        amount: 10, // Pay per property owned
+        house_cost: 0,
+        hotel_cost: 0,
+    },
+    CardData {
+        id: 14, // "Hackathon Prize"
+        effect_type: CardEffectType::Money,
+        amount: 150,
+        house_cost: 0,
+        hotel_cost: 0,
+    },
+    CardData {
+        id: 15, // "Whale Watching"
+        effect_type: CardEffectType::Move,
+        amount: 39, // Move to the last Dark Blue property
+        house_cost: 0,
+        hotel_cost: 0,
+    },
+    CardData {
+        id: 16, // "Bull Run"
+        effect_type: CardEffectType::Money,
+        amount: 50,
+        house_cost: 0,
+        hotel_cost: 0,
     },
 ];
 
 // Airdrop Chest Cards (Community Chest cards)
-pub const COMMUNITY_CHEST_CARDS: [CommunityChestCard; 5] = [
-    CommunityChestCard {
+pub const DEFAULT_COMMUNITY_CHEST_CARDS: [CardData; 16] = [
+    CardData {
         id: 1, // "Retroactive Airdrop!"
         effect_type: CardEffectType::CollectFromPlayers,
         amount: 50,
+        house_cost: 0,
+        hotel_cost: 0,
     },
-    CommunityChestCard {
+    CardData {
         id: 2, // "Staking Rewards"
         effect_type: CardEffectType::Money,
         amount: 100,
+        house_cost: 0,
+        hotel_cost: 0,
     },
-    CommunityChestCard {
+    CardData {
         id: 3, // "NFT Floor Sweep"
         effect_type: CardEffectType::Move,
         amount: 21, // Move to Free Airdrop Parking (position 21)
+        house_cost: 0,
+        hotel_cost: 0,
     },
-    CommunityChestCard {
+    CardData {
         id: 4, // "DAO Vote Win"
         effect_type: CardEffectType::RepairFree,
         amount: 0,
+        house_cost: 0,
+        hotel_cost: 0,
     },
-    CommunityChestCard {
+    CardData {
         id: 5, // "Wallet Drain Fee"
         effect_type: CardEffectType::Money,
         amount: -50,
+        house_cost: 0,
+        hotel_cost: 0,
+    },
+    CardData {
+        id: 6, // "Genesis Airdrop"
+        effect_type: CardEffectType::AdvanceToGo,
+        amount: 0,
+        house_cost: 0,
+        hotel_cost: 0,
+    },
+    CardData {
+        id: 7, // "Validator Audit"
+        effect_type: CardEffectType::GoToJail,
+        amount: 0,
+        house_cost: 0,
+        hotel_cost: 0,
+    },
+    CardData {
+        id: 8, // "Multisig Recovery"
+        effect_type: CardEffectType::GetOutOfJailFree,
+        amount: 0,
+        house_cost: 0,
+        hotel_cost: 0,
+    },
+    CardData {
+        id: 9, // "Infra Maintenance"
+        effect_type: CardEffectType::StreetRepairs,
+        amount: 0,
+        house_cost: 40,
+        hotel_cost: 115,
+    },
+    CardData {
+        id: 10, // "Grant Payout"
+        effect_type: CardEffectType::Money,
+        amount: 200,
+        house_cost: 0,
+        hotel_cost: 0,
+    },
+    CardData {
+        id: 11, // "Bug Bounty"
+        effect_type: CardEffectType::Money,
+        amount: 50,
+        house_cost: 0,
+        hotel_cost: 0,
+    },
+    CardData {
+        id: 12, // "RPC Bill"
+        effect_type: CardEffectType::Money,
+        amount: -100,
+        house_cost: 0,
+        hotel_cost: 0,
+    },
+    CardData {
+        id: 13, // "Governance Rewards"
+        effect_type: CardEffectType::CollectFromPlayers,
+        amount: 10,
+        house_cost: 0,
+        hotel_cost: 0,
+    },
+    CardData {
+        id: 14, // "LP Fees"
+        effect_type: CardEffectType::Money,
+        amount: 20,
+        house_cost: 0,
+        hotel_cost: 0,
+    },
+    CardData {
+        id: 15, // "Hardware Wallet"
+        effect_type: CardEffectType::Money,
+        amount: -50,
+        house_cost: 0,
+        hotel_cost: 0,
+    },
+    CardData {
+        id: 16, // "Liquid Staking Yield"
+        effect_type: CardEffectType::Money,
+        amount: 25,
+        house_cost: 0,
+        hotel_cost: 0,
     },
 ];
-
-// Festival effects (keeping original structure but updating for Solana theme)
-// pub const FESTIVAL_EFFECTS: [FestivalEffect; 4] = [
-//     FestivalEffect {
-//         id: 1, // "Validator Performance Bonus"
-//         amount: 100,
-//         is_positive: true,
-//     },
-//     FestivalEffect {
-//         id: 2, // "Successful DeFi Yield"
-//         amount: 150,
-//         is_positive: true,
-//     },
-//     FestivalEffect {
-//         id: 3, // "NFT Royalty Income"
-//         amount: 75,
-//         is_positive: true,
-//     },
-//     FestivalEffect {
-//         id: 4, // "Ecosystem Grant Awarded"
-//         amount: 200,
-//         is_positive: true,
-//     },
-// ];
@@ -15,6 +15,15 @@ pub enum GameError {
     #[msg("Game is not in progress")]
     GameNotInProgress,
 
+    #[msg("Game is not being started")]
+    GameNotStarting,
+
+    #[msg("Game start has stalled and can only be cancelled")]
+    StartStalled,
+
+    #[msg("Game start has not stalled yet")]
+    StartNotStalled,
+
     #[msg("Game has already ended")]
     GameAlreadyEnded,
 
@@ -227,6 +236,12 @@ pub enum GameError {
     #[msg("Community chest card not implemented")]
     CommunityChestCardNotImplemented,
 
+    #[msg("Invalid card deck")]
+    InvalidCardDeck,
+
+    #[msg("Jail card is already held by a player")]
+    JailCardAlreadyHeld,
+
     // Tax Errors
     #[msg("Tax amount calculation error")]
     TaxCalculationError,
@@ -247,6 +262,9 @@ pub enum GameError {
     #[msg("Bankruptcy check not required")]
     BankruptcyNotRequired,
 
+    #[msg("Outstanding debt must be settled first")]
+    DebtOutstanding,
+
     // Account and Authorization Errors
     #[msg("Unauthorized action")]
     Unauthorized,
@@ -263,6 +281,9 @@ pub enum GameError {
     #[msg("Invalid signer")]
     InvalidSigner,
 
+    #[msg("Platform is paused")]
+    PlatformPaused,
+
     // Math Errors
     #[msg("Arithmetic overflow")]
     ArithmeticOverflow,
@@ -279,6 +300,21 @@ pub enum GameError {
     #[msg("Invalid game configuration")]
     InvalidGameConfiguration,
 
+    #[msg("Game rules are outside the platform limits")]
+    InvalidGameRules,
+
+    #[msg("Max players must be between 2 and 8")]
+    InvalidMaxPlayers,
+
+    #[msg("Payout schedule must pay 1 to max players places and add up to 100%")]
+    InvalidPayoutSchedule,
+
+    #[msg("Commit frequency must be greater than zero")]
+    InvalidCommitFrequency,
+
+    #[msg("Validator is not approved by the platform")]
+    UnapprovedValidator,
+
     // Randomness Errors
     #[msg("Failed to generate random number")]
     RandomnessGenerationFailed,
@@ -289,6 +325,24 @@ pub enum GameError {
     #[msg("Randomness unavailable")]
     RandomnessUnavailable,
 
+    #[msg("Player has not committed randomness")]
+    MissingRandomnessCommitment,
+
+    #[msg("Randomness reveal does not match the commitment")]
+    InvalidRandomnessReveal,
+
+    #[msg("Fixed dice rolls and card draws are only allowed in local builds")]
+    RandomnessOverrideNotAllowed,
+
+    #[msg("Game uses the other randomness source")]
+    RandomnessModeMismatch,
+
+    #[msg("Randomness slot hash is still available")]
+    RandomnessSlotNotExpired,
+
+    #[msg("Randomness can only be re-committed between turns")]
+    RecommitDuringOwnTurn,
+
     // Time Errors
     #[msg("Invalid timestamp")]
     InvalidTimestamp,
@@ -354,6 +408,12 @@ pub enum GameError {
     #[msg("Too many active trades")]
     TooManyActiveTrades,
 
+    #[msg("Too many properties in trade")]
+    TooManyPropertiesInTrade,
+
+    #[msg("Property listed more than once in trade")]
+    DuplicatePropertyInTrade,
+
     // Token and Fee Errors
     #[msg("Missing required token accounts for entry fee")]
     MissingTokenAccounts,
@@ -373,6 +433,27 @@ pub enum GameError {
     #[msg("Token accounts provided for free game")]
     UnexpectedTokenAccounts,
 
+    #[msg("Platform fee exceeds the maximum")]
+    InvalidFeeBasisPoints,
+
+    #[msg("Missing SOL vault for native entry fee")]
+    MissingSolVault,
+
+    #[msg("Invalid SOL vault PDA")]
+    InvalidSolVault,
+
+    #[msg("Token mint has an unsupported extension")]
+    UnsupportedMintExtension,
+
+    #[msg("Token mint is not allowed on this platform")]
+    MintNotAllowed,
+
+    #[msg("Entry fee is outside the allowed range for this mint")]
+    EntryFeeOutOfRange,
+
+    #[msg("Only games with a token vault accept sponsors")]
+    GameNotSponsorable,
+
     #[msg("Game is already ending")]
     GameAlreadyEnding,
 
@@ -385,12 +466,15 @@ pub enum GameError {
     #[msg("No winner has been declared")]
     NoWinnerDeclared,
 
-    #[msg("You are not the winner")]
+    #[msg("You did not finish in a paid placement")]
     NotWinner,
 
     #[msg("No prize to claim")]
     NoPrizeToClaim,
 
+    #[msg("Prizes can still be claimed")]
+    ClaimWindowOpen,
+
     #[msg("No active players found")]
     NoActivePlayers,
 
@@ -1,225 +1,332 @@
-// use crate::constants::*;
-// use crate::error::GameError;
-// use crate::state::*;
-// use crate::utils::*;
-// use anchor_lang::prelude::*;
-
-// #[derive(Accounts)]
-// #[instruction(property_position: u8)]
-// pub struct StartAuction<'info> {
-//     #[account(
-//         mut,
-//         seeds = [b"game", game.authority.as_ref(), &game.game_id.to_le_bytes()],
-//         bump = game.bump,
-//         constraint = game.game_status == GameStatus::InProgress @ GameError::GameNotInProgress
-//     )]
-//     pub game: Account<'info, GameState>,
-
-//     #[account(
-//         init,
-//         payer = initiator,
-//         space = 8 + AuctionState::INIT_SPACE,
-//         seeds = [b"auction", game.key().as_ref(), &property_position.to_le_bytes()],
-//         bump
-//     )]
-//     pub auction: Account<'info, AuctionState>,
-
-//     #[account(
-//         seeds = [b"property", game.key().as_ref(), &property_position.to_le_bytes()],
-//         bump
-//     )]
-//     pub property_state: Account<'info, PropertyState>,
-
-//     #[account(mut)]
-//     pub initiator: Signer<'info>,
-
-//     pub system_program: Program<'info, System>,
-//     pub clock: Sysvar<'info, Clock>,
-// }
-
-// pub fn start_auction_handler(ctx: Context<StartAuction>, property_position: u8) -> Result<()> {
-//     let game = &ctx.accounts.game;
-//     let auction = &mut ctx.accounts.auction;
-//     let property_state = &ctx.accounts.property_state;
-//     let clock = &ctx.accounts.clock;
-
-//     // Validate property is unowned
-//     if property_state.owner.is_some() {
-//         return Err(GameError::PropertyAlreadyOwned.into());
-//     }
-
-//     // Validate position is purchasable
-//     if !is_property_purchasable(property_position) {
-//         return Err(GameError::PropertyNotPurchasable.into());
-//     }
-
-//     // Initialize auction
-//     auction.game = game.key();
-//     auction.property_position = property_position;
-//     auction.current_bid = 0;
-//     auction.highest_bidder = None;
-//     auction.started_at = clock.unix_timestamp;
-//     auction.ends_at = clock.unix_timestamp + AUCTION_DURATION_SECONDS;
-//     auction.is_active = true;
-//     auction.bump = ctx.bumps.auction;
-
-//     msg!(
-//         "Auction started for property at position {}",
-//         property_position
-//     );
-
-//     Ok(())
-// }
-
-// #[derive(Accounts)]
-// pub struct PlaceBid<'info> {
-//     #[account(
-//         mut,
-//         seeds = [b"auction", auction.game.as_ref(), &auction.property_position.to_le_bytes()],
-//         bump = auction.bump,
-//         constraint = auction.is_active @ GameError::AuctionNotActive
-//     )]
-//     pub auction: Account<'info, AuctionState>,
-
-//     #[account(
-//         seeds = [b"game", game.authority.as_ref(), &game.game_id.to_le_bytes()],
-//         bump = game.bump
-//     )]
-//     pub game: Account<'info, GameState>,
-
-//     #[account(
-//         seeds = [b"player", game.key().as_ref(), bidder.key().as_ref()],
-//         bump
-//     )]
-//     pub bidder_state: Account<'info, PlayerState>,
-
-//     #[account(mut)]
-//     pub bidder: Signer<'info>,
-
-//     pub clock: Sysvar<'info, Clock>,
-// }
-
-// pub fn place_bid_handler(ctx: Context<PlaceBid>, bid_amount: u64) -> Result<()> {
-//     let auction = &mut ctx.accounts.auction;
-//     let bidder_state = &ctx.accounts.bidder_state;
-//     let bidder_pubkey = ctx.accounts.bidder.key();
-//     let clock = &ctx.accounts.clock;
-
-//     // Check if auction has ended
-//     if clock.unix_timestamp > auction.ends_at {
-//         return Err(GameError::AuctionEnded.into());
-//     }
-
-//     // Validate bid amount
-//     if bid_amount <= auction.current_bid {
-//         return Err(GameError::BidTooLow.into());
-//     }
-
-//     // Check if bidder has enough money
-//     if bidder_state.cash_balance < bid_amount {
-//         return Err(GameError::InsufficientFunds.into());
-//     }
-
-//     // Update auction
-//     auction.current_bid = bid_amount;
-//     auction.highest_bidder = Some(bidder_pubkey);
-
-//     msg!("New bid of ${} placed by {}", bid_amount, bidder_pubkey);
-
-//     Ok(())
-// }
-
-// #[derive(Accounts)]
-// pub struct EndAuction<'info> {
-//     #[account(
-//         mut,
-//         seeds = [b"auction", auction.game.as_ref(), &auction.property_position.to_le_bytes()],
-//         bump = auction.bump,
-//         constraint = auction.is_active @ GameError::AuctionNotActive,
-//         close = closer
-//     )]
-//     pub auction: Account<'info, AuctionState>,
-
-//     #[account(
-//         mut,
-//         seeds = [b"game", game.authority.as_ref(), &game.game_id.to_le_bytes()],
-//         bump = game.bump
-//     )]
-//     pub game: Account<'info, GameState>,
-
-//     #[account(
-//         mut,
-//         seeds = [b"property", game.key().as_ref(), &auction.property_position.to_le_bytes()],
-//         bump
-//     )]
-//     pub property_state: Account<'info, PropertyState>,
-
-//     #[account(
-//         mut,
-//         seeds = [b"player", game.key().as_ref(), winner.key().as_ref()],
-//         bump
-//     )]
-//     pub winner_state: Account<'info, PlayerState>,
-
-//     #[account(mut)]
-//     pub closer: Signer<'info>,
-
-//     /// CHECK: This is validated by the winner_state account constraint
-//     pub winner: UncheckedAccount<'info>,
-
-//     pub clock: Sysvar<'info, Clock>,
-// }
-
-// pub fn end_auction_handler(ctx: Context<EndAuction>) -> Result<()> {
-//     let auction = &mut ctx.accounts.auction;
-//     let property_state = &mut ctx.accounts.property_state;
-//     let winner_state = &mut ctx.accounts.winner_state;
-//     let winner_pubkey = ctx.accounts.winner.key();
-//     let clock = &ctx.accounts.clock;
-
-//     // Check if auction time has ended
-//     if clock.unix_timestamp <= auction.ends_at {
-//         return Err(GameError::AuctionStillActive.into());
-//     }
-
-//     auction.is_active = false;
-
-//     if let Some(highest_bidder) = auction.highest_bidder {
-//         // Validate winner matches highest bidder
-//         if highest_bidder != winner_pubkey {
-//             return Err(GameError::InvalidAccount.into());
-//         }
-
-//         // Transfer property
-//         property_state.owner = Some(winner_pubkey);
-
-//         // Deduct money from winner
-//         winner_state.cash_balance = winner_state
-//             .cash_balance
-//             .checked_sub(auction.current_bid)
-//             .ok_or(GameError::ArithmeticUnderflow)?;
-
-//         // Add property to winner's owned properties
-//         winner_state
-//             .properties_owned
-//             .push(auction.property_position);
-
-//         // Update net worth
-//         if let Some(property_data) = get_property_data(auction.property_position) {
-//             winner_state.net_worth = winner_state
-//                 .net_worth
-//                 .checked_add(property_data.price)
-//                 .ok_or(GameError::ArithmeticOverflow)?;
-//         }
-
-//         msg!(
-//             "Auction won by {} for ${}",
-//             highest_bidder,
-//             auction.current_bid
-//         );
-//     } else {
-//         msg!("Auction ended with no bids");
-//     }
-
-//     Ok(())
-// }
+use crate::constants::*;
+use crate::error::GameError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct StartAuction<'info> {
+    #[account(
+        mut,
+        seeds = [b"game", game.config_id.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump,
+        constraint = game.game_status == GameStatus::InProgress @ GameError::GameNotInProgress
+    )]
+    pub game: Box<Account<'info, GameState>>,
+
+    #[account(
+        mut,
+        seeds = [b"player", game.key().as_ref(), player.key().as_ref()],
+        bump,
+        constraint = !player_state.needs_bankruptcy_check @ GameError::DebtOutstanding
+    )]
+    pub player_state: Box<Account<'info, PlayerState>>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn start_auction_handler(ctx: Context<StartAuction>, position: u8) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let player_state = &mut ctx.accounts.player_state;
+    let player_pubkey = ctx.accounts.player.key();
+    let clock = &ctx.accounts.clock;
+
+    // Validate turn
+    let player_index = game
+        .players
+        .iter()
+        .position(|&p| p == player_pubkey)
+        .ok_or(GameError::PlayerNotFound)?;
+
+    require!(
+        game.current_turn == player_index as u8,
+        GameError::NotPlayerTurn
+    );
+
+    // Only the property the player is deciding on can be auctioned
+    require!(
+        player_state.needs_property_action,
+        GameError::InvalidSpecialSpaceAction
+    );
+
+    require!(
+        player_state.pending_property_position == Some(position),
+        GameError::InvalidPropertyPosition
+    );
+
+    player_state.record_action(clock);
+
+    open_property_auction(game, player_state, position, clock)
+}
+
+/// Opens an auction for an unowned property the current player declined.
+/// Shared by `start_auction` and `decline_property_v2`.
+pub fn open_property_auction(
+    game: &mut Account<'_, GameState>,
+    player_state: &mut PlayerState,
+    position: u8,
+    clock: &Sysvar<Clock>,
+) -> Result<()> {
+    require!(!game.has_active_auction(), GameError::AuctionAlreadyExists);
+
+    require!(
+        is_property_purchasable(position),
+        GameError::PropertyNotPurchasable
+    );
+
+    let property = game.get_property(position)?;
+    require!(property.owner.is_none(), GameError::PropertyAlreadyOwned);
+
+    let ends_at = clock
+        .unix_timestamp
+        .checked_add(AUCTION_DURATION_SECONDS)
+        .ok_or(GameError::ArithmeticOverflow)?;
+
+    game.active_auction = Some(AuctionInfo {
+        property_position: position,
+        initiator: player_state.wallet,
+        current_bid: 0,
+        highest_bidder: None,
+        started_at: clock.unix_timestamp,
+        ends_at,
+    });
+
+    // The property decision is resolved by the auction
+    player_state.needs_property_action = false;
+    player_state.pending_property_position = None;
+
+    game.turn_started_at = clock.unix_timestamp;
+
+    msg!(
+        "Auction started for property {} by {}. Bidding ends at {}",
+        position,
+        player_state.wallet,
+        ends_at
+    );
+
+    emit!(AuctionStarted {
+        game: game.key(),
+        initiator: player_state.wallet,
+        property_position: position,
+        ends_at,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct PlaceBid<'info> {
+    #[account(
+        mut,
+        seeds = [b"game", game.config_id.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump,
+        constraint = game.game_status == GameStatus::InProgress @ GameError::GameNotInProgress
+    )]
+    pub game: Box<Account<'info, GameState>>,
+
+    #[account(
+        mut,
+        seeds = [b"player", game.key().as_ref(), bidder.key().as_ref()],
+        bump,
+        constraint = !bidder_state.needs_bankruptcy_check @ GameError::DebtOutstanding,
+        constraint = !bidder_state.is_bankrupt @ GameError::PlayerBankrupt
+    )]
+    pub bidder_state: Box<Account<'info, PlayerState>>,
+
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn place_bid_handler(ctx: Context<PlaceBid>, bid_amount: u64) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let bidder_state = &mut ctx.accounts.bidder_state;
+    let bidder_pubkey = ctx.accounts.bidder.key();
+    let clock = &ctx.accounts.clock;
+
+    // Any player still in the game can bid
+    let bidder_index = game
+        .players
+        .iter()
+        .position(|&p| p == bidder_pubkey)
+        .ok_or(GameError::PlayerNotFound)?;
+
+    require!(
+        !game.player_eliminated[bidder_index],
+        GameError::PlayerBankrupt
+    );
+
+    let game_key = game.key();
+    let auction = game
+        .active_auction
+        .as_mut()
+        .ok_or(GameError::AuctionNotActive)?;
+
+    require!(
+        clock.unix_timestamp < auction.ends_at,
+        GameError::AuctionEnded
+    );
+
+    require!(
+        auction.highest_bidder != Some(bidder_pubkey),
+        GameError::AlreadyHighestBidder
+    );
+
+    require!(bid_amount > auction.current_bid, GameError::BidTooLow);
+
+    require!(
+        bidder_state.cash_balance >= bid_amount,
+        GameError::InsufficientFunds
+    );
+
+    bidder_state.record_action(clock);
+
+    auction.current_bid = bid_amount;
+    auction.highest_bidder = Some(bidder_pubkey);
+
+    msg!(
+        "New bid of ${} placed by {} for property {}",
+        bid_amount,
+        bidder_pubkey,
+        auction.property_position
+    );
+
+    emit!(AuctionBidPlaced {
+        game: game_key,
+        bidder: bidder_pubkey,
+        property_position: auction.property_position,
+        bid_amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Settling is permissionless once the bidding deadline has passed
+#[derive(Accounts)]
+pub struct SettleAuction<'info> {
+    #[account(
+        mut,
+        seeds = [b"game", game.config_id.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump,
+        constraint = game.game_status == GameStatus::InProgress @ GameError::GameNotInProgress
+    )]
+    pub game: Box<Account<'info, GameState>>,
+
+    // Required when the auction has a highest bidder
+    #[account(
+        mut,
+        seeds = [b"player", game.key().as_ref(), winner_state.wallet.as_ref()],
+        bump
+    )]
+    pub winner_state: Option<Box<Account<'info, PlayerState>>>,
+
+    #[account(mut)]
+    pub settler: Signer<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn settle_auction_handler(ctx: Context<SettleAuction>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let clock = &ctx.accounts.clock;
+    let settler_pubkey = ctx.accounts.settler.key();
+
+    let auction = game
+        .active_auction
+        .clone()
+        .ok_or(GameError::AuctionNotActive)?;
+
+    require!(
+        clock.unix_timestamp >= auction.ends_at,
+        GameError::AuctionStillActive
+    );
+
+    let position = auction.property_position;
+    let mut winner: Option<Pubkey> = None;
+
+    if let Some(highest_bidder) = auction.highest_bidder {
+        let winner_state = ctx
+            .accounts
+            .winner_state
+            .as_mut()
+            .ok_or(GameError::MissingPlayerAccount)?;
+
+        require!(
+            winner_state.wallet == highest_bidder,
+            GameError::InvalidPlayerAccount
+        );
+
+        let bidder_index = game
+            .players
+            .iter()
+            .position(|&p| p == highest_bidder)
+            .ok_or(GameError::PlayerNotFound)?;
+
+        // The winning bid only stands if the bidder is still solvent for it
+        if !game.player_eliminated[bidder_index]
+            && !winner_state.is_bankrupt
+            && winner_state.cash_balance >= auction.current_bid
+        {
+            let property_data = get_property_data(position)?;
+
+            let property = game.get_property_mut(position)?;
+            require!(property.owner.is_none(), GameError::PropertyAlreadyOwned);
+            property.owner = Some(highest_bidder);
+
+            winner_state.cash_balance = winner_state
+                .cash_balance
+                .checked_sub(auction.current_bid)
+                .ok_or(GameError::ArithmeticUnderflow)?;
+
+            if !winner_state.properties_owned.contains(&position) {
+                winner_state.properties_owned.push(position);
+            }
+
+            winner_state.net_worth = winner_state
+                .net_worth
+                .checked_add(property_data.price)
+                .ok_or(GameError::ArithmeticOverflow)?;
+
+            winner = Some(highest_bidder);
+
+            msg!(
+                "Auction for property {} won by {} for ${}",
+                position,
+                highest_bidder,
+                auction.current_bid
+            );
+        } else {
+            msg!(
+                "Highest bidder {} can no longer pay ${}. Property {} stays with the bank",
+                highest_bidder,
+                auction.current_bid,
+                position
+            );
+        }
+    } else {
+        msg!("Auction for property {} ended with no bids", position);
+    }
+
+    game.active_auction = None;
+    game.turn_started_at = clock.unix_timestamp;
+
+    emit!(AuctionSettled {
+        game: game.key(),
+        property_position: position,
+        winner,
+        winning_bid: if winner.is_some() {
+            auction.current_bid
+        } else {
+            0
+        },
+        settled_by: settler_pubkey,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
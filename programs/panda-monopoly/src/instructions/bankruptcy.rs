@@ -21,6 +21,14 @@ pub struct DeclareBankruptcy<'info> {
     )]
     pub player_state: Box<Account<'info, PlayerState>>,
 
+    // Required when the debt is owed to another player
+    #[account(
+        mut,
+        seeds = [b"player", game.key().as_ref(), creditor_state.wallet.as_ref()],
+        bump
+    )]
+    pub creditor_state: Option<Box<Account<'info, PlayerState>>>,
+
     #[account(mut)]
     pub player: Signer<'info>,
 
@@ -47,88 +55,65 @@ pub fn declare_bankruptcy_handler(ctx: Context<DeclareBankruptcy>) -> Result<()>
     //     return Err(GameError::NotPlayerTurn.into());
     // }
 
-    player_state.is_bankrupt = true;
-
-    let mut total_liquidation_value = 0u64;
-    let mut houses_returned = 0u8;
-    let mut hotels_returned = 0u8;
-
-    // Process all properties owned by the player
-    for &position in &player_state.properties_owned.clone() {
-        // Get property from GameState
-        let property = game.get_property_mut(position)?;
-
-        // Verify this property belongs to the bankrupt player
-        if property.owner == Some(player_pubkey) {
-            // Get static property data for costs
-            let property_data = get_property_data(position)?;
-
-            // Calculate building liquidation value
-            let building_value = calculate_building_liquidation_value(property, property_data)?;
-            total_liquidation_value = total_liquidation_value
-                .checked_add(building_value)
-                .ok_or(GameError::ArithmeticOverflow)?;
+    // Bankruptcy is the last resort once selling and mortgaging can't cover the debt
+    require!(
+        player_state.needs_bankruptcy_check,
+        GameError::BankruptcyNotRequired
+    );
 
-            // Return buildings to bank
-            if property.houses > 0 {
-                houses_returned = houses_returned
-                    .checked_add(property.houses)
-                    .ok_or(GameError::ArithmeticOverflow)?;
-                property.houses = 0;
-            }
+    let liquidation_value = calculate_liquidation_value(game, &player_pubkey)?
+        .checked_add(player_state.cash_balance)
+        .ok_or(GameError::ArithmeticOverflow)?;
+    require!(
+        liquidation_value < player_state.debt_amount,
+        GameError::CannotDeclareBankruptcyWithAssets
+    );
 
-            if property.has_hotel {
-                hotels_returned = hotels_returned
-                    .checked_add(1)
-                    .ok_or(GameError::ArithmeticOverflow)?;
-                property.has_hotel = false;
-            }
+    // The estate goes to the creditor if the debt is owed to a player still in the game
+    let creditor_state = resolve_creditor(player_state, ctx.accounts.creditor_state.as_mut())?;
 
-            // Calculate mortgage value if not already mortgaged
-            if !property.is_mortgaged {
-                let mortgage_value = property_data.mortgage_value;
-                total_liquidation_value = total_liquidation_value
-                    .checked_add(mortgage_value)
-                    .ok_or(GameError::ArithmeticOverflow)?;
-            }
+    player_state.is_bankrupt = true;
 
-            // Clear ownership
-            property.owner = None;
-            property.is_mortgaged = false;
+    let (total_liquidation_value, remaining_cash) = match creditor_state {
+        Some(creditor_state) => {
+            let creditor = creditor_state.wallet;
+            let (liquidation_value, cash) =
+                transfer_estate_to_creditor(game, player_state, creditor_state)?;
+
+            msg!(
+                "Player {} declared bankruptcy. Estate handed to creditor {}: ${} from buildings, ${} cash.",
+                player_pubkey,
+                creditor,
+                liquidation_value,
+                cash
+            );
+
+            (liquidation_value, cash)
         }
-    }
-
-    // Return buildings to bank
-    game.houses_remaining = game
-        .houses_remaining
-        .checked_add(houses_returned)
-        .ok_or(GameError::ArithmeticOverflow)?;
-
-    game.hotels_remaining = game
-        .hotels_remaining
-        .checked_add(hotels_returned)
-        .ok_or(GameError::ArithmeticOverflow)?;
-
-    // Add liquidation value to bank
-    game.bank_balance = game
-        .bank_balance
-        .checked_add(total_liquidation_value)
-        .ok_or(GameError::ArithmeticOverflow)?;
+        None => {
+            let (liquidation_value, cash, houses_returned, hotels_returned) =
+                transfer_estate_to_bank(game, player_state)?;
+
+            msg!(
+                "Player {} declared bankruptcy. Liquidated ${} in assets, ${} cash transferred to bank. {} houses and {} hotels returned to bank.",
+                player_pubkey,
+                liquidation_value,
+                cash,
+                houses_returned,
+                hotels_returned
+            );
+
+            (liquidation_value, cash)
+        }
+    };
 
     // Clear bankruptcy check flag
-    player_state.needs_bankruptcy_check = false;
-
-    // Transfer all remaining cash to the bank
-    let remaining_cash = player_state.cash_balance;
-    game.bank_balance = game
-        .bank_balance
-        .checked_add(remaining_cash)
-        .ok_or(GameError::ArithmeticOverflow)?;
+    player_state.clear_debt();
 
     player_state.cash_balance = 0;
     player_state.net_worth = 0;
     player_state.properties_owned.clear();
-    player_state.get_out_of_jail_cards = 0;
+    player_state.get_out_of_jail_cards.clear();
 
     // Clear all player flags and reset position
     reset_player_state_for_bankruptcy(player_state);
@@ -139,15 +124,6 @@ pub fn declare_bankruptcy_handler(ctx: Context<DeclareBankruptcy>) -> Result<()>
     // Update game timestamp
     game.turn_started_at = clock.unix_timestamp;
 
-    msg!(
-        "Player {} declared bankruptcy. Liquidated ${} in assets, ${} cash transferred to bank. {} houses and {} hotels returned to bank.",
-        player_pubkey,
-        total_liquidation_value,
-        remaining_cash,
-        houses_returned,
-        hotels_returned
-    );
-
     if game.check_bankruptcy_end_condition() {
         game.end_condition_met = true;
         game.end_reason = Some(GameEndReason::BankruptcyVictory);
@@ -201,6 +177,384 @@ pub fn declare_bankruptcy_handler(ctx: Context<DeclareBankruptcy>) -> Result<()>
     Ok(())
 }
 
+#[derive(Accounts)]
+pub struct SettleDebt<'info> {
+    #[account(
+        mut,
+        seeds = [b"game", game.config_id.as_ref(), &game.game_id.to_le_bytes().as_ref()],
+        bump = game.bump,
+        constraint = game.game_status == GameStatus::InProgress @ GameError::GameNotInProgress
+    )]
+    pub game: Box<Account<'info, GameState>>,
+
+    #[account(
+        mut,
+        seeds = [b"player", game.key().as_ref(), player.key().as_ref()],
+        bump,
+        constraint = !player_state.is_bankrupt @ GameError::PlayerBankrupt
+    )]
+    pub player_state: Box<Account<'info, PlayerState>>,
+
+    // Required when the debt is owed to another player
+    #[account(
+        mut,
+        seeds = [b"player", game.key().as_ref(), creditor_state.wallet.as_ref()],
+        bump
+    )]
+    pub creditor_state: Option<Box<Account<'info, PlayerState>>>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn settle_debt_handler(ctx: Context<SettleDebt>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let player_state = &mut ctx.accounts.player_state;
+    let player_pubkey = ctx.accounts.player.key();
+    let clock = &ctx.accounts.clock;
+
+    let player_index = game
+        .players
+        .iter()
+        .position(|&p| p == player_pubkey)
+        .ok_or(GameError::PlayerNotFound)?;
+
+    require!(
+        player_state.needs_bankruptcy_check,
+        GameError::BankruptcyNotRequired
+    );
+
+    let amount = player_state.debt_amount;
+    let creditor = player_state.debt_creditor;
+    require!(
+        player_state.cash_balance >= amount,
+        GameError::InsufficientFunds
+    );
+
+    player_state.record_action(clock);
+
+    let creditor_state = resolve_creditor(player_state, ctx.accounts.creditor_state.as_mut())?;
+
+    player_state.cash_balance -= amount;
+    player_state.net_worth = player_state.net_worth.saturating_sub(amount);
+
+    match creditor_state {
+        Some(creditor_state) => {
+            creditor_state.cash_balance = creditor_state
+                .cash_balance
+                .checked_add(amount)
+                .ok_or(GameError::ArithmeticOverflow)?;
+
+            creditor_state.net_worth = creditor_state
+                .net_worth
+                .checked_add(amount)
+                .ok_or(GameError::ArithmeticOverflow)?;
+        }
+        None => game.add_to_free_parking(amount),
+    }
+
+    player_state.resolve_settled_landing();
+    player_state.clear_debt();
+
+    // Debts owed off-turn don't extend the current player's turn
+    if game.current_turn == player_index as u8 {
+        game.turn_started_at = clock.unix_timestamp;
+    }
+
+    msg!("Player {} settled a debt of ${}", player_pubkey, amount);
+
+    emit!(DebtSettled {
+        game: game.key(),
+        player: player_pubkey,
+        creditor,
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Returns the creditor's state when the debt is owed to a player who is
+/// still in the game. Debts to the bank, or to a player who has since gone
+/// bankrupt, are settled with the bank.
+pub fn resolve_creditor<'a, 'info>(
+    debtor_state: &PlayerState,
+    creditor_state: Option<&'a mut Box<Account<'info, PlayerState>>>,
+) -> Result<Option<&'a mut PlayerState>> {
+    let Some(creditor) = debtor_state.debt_creditor else {
+        return Ok(None);
+    };
+
+    let creditor_state = creditor_state.ok_or(GameError::MissingPlayerAccount)?;
+    require!(
+        creditor_state.wallet == creditor,
+        GameError::InvalidPlayerAccount
+    );
+
+    if creditor_state.is_bankrupt {
+        return Ok(None);
+    }
+
+    Ok(Some(creditor_state))
+}
+
+/// Liquidates the bankrupt player's estate to the bank. Buildings are sold
+/// back, properties return unowned and unmortgaged, and the bank receives
+/// the liquidation value plus any remaining cash.
+/// Returns (liquidation value, cash, houses returned, hotels returned).
+pub fn transfer_estate_to_bank(
+    game: &mut GameState,
+    debtor_state: &PlayerState,
+) -> Result<(u64, u64, u8, u8)> {
+    let debtor = debtor_state.wallet;
+    let mut total_liquidation_value = 0u64;
+    let mut houses_returned = 0u8;
+    let mut hotels_returned = 0u8;
+
+    // Process all properties owned by the player
+    for &position in &debtor_state.properties_owned {
+        // Get property from GameState
+        let property = game.get_property_mut(position)?;
+
+        // Verify this property belongs to the bankrupt player
+        if property.owner == Some(debtor) {
+            // Get static property data for costs
+            let property_data = get_property_data(position)?;
+
+            // Calculate building liquidation value
+            let building_value = calculate_building_liquidation_value(property, property_data)?;
+            total_liquidation_value = total_liquidation_value
+                .checked_add(building_value)
+                .ok_or(GameError::ArithmeticOverflow)?;
+
+            // Return buildings to bank
+            let (houses, hotels) = strip_buildings(property);
+            houses_returned = houses_returned
+                .checked_add(houses)
+                .ok_or(GameError::ArithmeticOverflow)?;
+            hotels_returned = hotels_returned
+                .checked_add(hotels)
+                .ok_or(GameError::ArithmeticOverflow)?;
+
+            // Calculate mortgage value if not already mortgaged
+            if !property.is_mortgaged {
+                let mortgage_value = property_data.mortgage_value;
+                total_liquidation_value = total_liquidation_value
+                    .checked_add(mortgage_value)
+                    .ok_or(GameError::ArithmeticOverflow)?;
+            }
+
+            // Clear ownership
+            property.owner = None;
+            property.is_mortgaged = false;
+        }
+    }
+
+    return_buildings_to_bank(game, houses_returned, hotels_returned)?;
+
+    // Forfeited jail cards go back into the decks
+    for &card in debtor_state.get_out_of_jail_cards.iter() {
+        game.return_jail_card(card);
+    }
+
+    // Add liquidation value and remaining cash to bank
+    let remaining_cash = debtor_state.cash_balance;
+    game.bank_balance = game
+        .bank_balance
+        .checked_add(total_liquidation_value)
+        .and_then(|balance| balance.checked_add(remaining_cash))
+        .ok_or(GameError::ArithmeticOverflow)?;
+
+    Ok((
+        total_liquidation_value,
+        remaining_cash,
+        houses_returned,
+        hotels_returned,
+    ))
+}
+
+/// Hands the bankrupt player's estate to the player they owe. Buildings are
+/// sold back to the bank at half price and the proceeds go to the creditor
+/// along with the remaining cash. Properties move over as they are, still
+/// mortgaged, and the creditor pays the 10% interest on each mortgaged one
+/// straight away. Interest the creditor can't cover is owed to the bank.
+/// Returns (building liquidation value, cash transferred).
+pub fn transfer_estate_to_creditor(
+    game: &mut GameState,
+    debtor_state: &PlayerState,
+    creditor_state: &mut PlayerState,
+) -> Result<(u64, u64)> {
+    let debtor = debtor_state.wallet;
+    let creditor = creditor_state.wallet;
+    let mut building_proceeds = 0u64;
+    let mut interest_due = 0u64;
+    let mut property_value = 0u64;
+    let mut houses_returned = 0u8;
+    let mut hotels_returned = 0u8;
+
+    for &position in &debtor_state.properties_owned {
+        let property = game.get_property_mut(position)?;
+
+        if property.owner == Some(debtor) {
+            let property_data = get_property_data(position)?;
+
+            // Buildings can't change hands, sell them back first
+            let building_value = calculate_building_liquidation_value(property, property_data)?;
+            building_proceeds = building_proceeds
+                .checked_add(building_value)
+                .ok_or(GameError::ArithmeticOverflow)?;
+
+            let (houses, hotels) = strip_buildings(property);
+            houses_returned = houses_returned
+                .checked_add(houses)
+                .ok_or(GameError::ArithmeticOverflow)?;
+            hotels_returned = hotels_returned
+                .checked_add(hotels)
+                .ok_or(GameError::ArithmeticOverflow)?;
+
+            if property.is_mortgaged {
+                interest_due = interest_due
+                    .checked_add(property_data.mortgage_value / 10)
+                    .ok_or(GameError::ArithmeticOverflow)?;
+                property_value = property_value
+                    .checked_add(property_data.price - property_data.mortgage_value)
+                    .ok_or(GameError::ArithmeticOverflow)?;
+            } else {
+                property_value = property_value
+                    .checked_add(property_data.price)
+                    .ok_or(GameError::ArithmeticOverflow)?;
+            }
+
+            // Ownership moves with the mortgage intact
+            property.owner = Some(creditor);
+
+            if !creditor_state.properties_owned.contains(&position) {
+                creditor_state.properties_owned.push(position);
+            }
+        }
+    }
+
+    return_buildings_to_bank(game, houses_returned, hotels_returned)?;
+
+    let remaining_cash = debtor_state.cash_balance;
+    let cash_received = remaining_cash
+        .checked_add(building_proceeds)
+        .ok_or(GameError::ArithmeticOverflow)?;
+
+    creditor_state.cash_balance = creditor_state
+        .cash_balance
+        .checked_add(cash_received)
+        .ok_or(GameError::ArithmeticOverflow)?;
+
+    creditor_state
+        .get_out_of_jail_cards
+        .extend_from_slice(&debtor_state.get_out_of_jail_cards);
+
+    // Interest on inherited mortgages is paid to the bank as far as the creditor can
+    let interest_paid = interest_due.min(creditor_state.cash_balance);
+    creditor_state.cash_balance -= interest_paid;
+    game.bank_balance = game
+        .bank_balance
+        .checked_add(interest_paid)
+        .ok_or(GameError::ArithmeticOverflow)?;
+
+    // The rest becomes a bank debt the creditor settles or goes bankrupt over,
+    // unless they already owe another player, then the bank writes it off
+    let interest_unpaid = interest_due - interest_paid;
+    if interest_unpaid > 0 {
+        if creditor_state.can_record_debt(None) {
+            creditor_state.record_debt(None, interest_unpaid)?;
+
+            msg!(
+                "Creditor {} owes the bank ${} in mortgage interest",
+                creditor,
+                interest_unpaid
+            );
+        } else {
+            msg!(
+                "Creditor {} already owes ${}, the bank writes off ${} in mortgage interest",
+                creditor,
+                creditor_state.debt_amount,
+                interest_unpaid
+            );
+        }
+    }
+
+    creditor_state.net_worth = creditor_state
+        .net_worth
+        .checked_add(cash_received)
+        .and_then(|worth| worth.checked_add(property_value))
+        .ok_or(GameError::ArithmeticOverflow)?
+        .saturating_sub(interest_paid);
+
+    msg!(
+        "Creditor {} received {} properties, ${} cash and paid ${} mortgage interest",
+        creditor,
+        debtor_state.properties_owned.len(),
+        cash_received,
+        interest_paid
+    );
+
+    Ok((building_proceeds, remaining_cash))
+}
+
+/// Removes all buildings from a property. Returns (houses, hotels) removed.
+fn strip_buildings(property: &mut PropertyInfo) -> (u8, u8) {
+    let houses = property.houses;
+    let hotels = if property.has_hotel { 1 } else { 0 };
+
+    property.houses = 0;
+    property.has_hotel = false;
+
+    (houses, hotels)
+}
+
+fn return_buildings_to_bank(game: &mut GameState, houses: u8, hotels: u8) -> Result<()> {
+    game.houses_remaining = game
+        .houses_remaining
+        .checked_add(houses)
+        .ok_or(GameError::ArithmeticOverflow)?;
+
+    game.hotels_remaining = game
+        .hotels_remaining
+        .checked_add(hotels)
+        .ok_or(GameError::ArithmeticOverflow)?;
+
+    Ok(())
+}
+
+/// What the player could still raise by selling every building back at half
+/// price and mortgaging every unmortgaged property. Properties that are
+/// already mortgaged can't raise anything more.
+fn calculate_liquidation_value(game: &GameState, player: &Pubkey) -> Result<u64> {
+    let mut value = 0u64;
+
+    for (position, property) in game.properties.iter().enumerate() {
+        if property.owner.as_ref() != Some(player) {
+            continue;
+        }
+
+        let property_data = get_property_data(position as u8)?;
+
+        value = value
+            .checked_add(calculate_building_liquidation_value(
+                property,
+                property_data,
+            )?)
+            .ok_or(GameError::ArithmeticOverflow)?;
+
+        if !property.is_mortgaged {
+            value = value
+                .checked_add(property_data.mortgage_value)
+                .ok_or(GameError::ArithmeticOverflow)?;
+        }
+    }
+
+    Ok(value)
+}
+
 fn calculate_building_liquidation_value(
     property: &PropertyInfo,
     property_data: &crate::constants::PropertyData,
@@ -246,6 +600,9 @@ fn remove_player_from_game(game: &mut GameState, player_index: u8) -> Result<()>
     if (player_index as usize) < game.players.len() {
         // game.players[player_index as usize] = Pubkey::default();
         game.player_eliminated[player_index as usize] = true;
+
+        let player = game.players[player_index as usize];
+        game.elimination_order.push(player);
     }
 
     game.current_players = game
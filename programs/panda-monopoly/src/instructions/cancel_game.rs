@@ -1,5 +1,7 @@
+use crate::constants::MAX_PLAYERS_PER_BATCH;
 use crate::error::GameError;
 use crate::state::*;
+use crate::utils::{harvest_withheld_fees, has_withheld_transfer_fees, transfer_from_sol_vault};
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{
     close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
@@ -12,26 +14,33 @@ pub struct CancelGame<'info> {
         mut,
         seeds = [b"game", game.config_id.as_ref(), &game.game_id.to_le_bytes()],
         bump = game.bump,
-        constraint = game.game_status == GameStatus::WaitingForPlayers @ GameError::GameAlreadyStarted,
-        constraint = game.creator == creator.key() @ GameError::Unauthorized,
-        close = creator
+        constraint = matches!(game.game_status, GameStatus::WaitingForPlayers | GameStatus::Starting | GameStatus::Cancelling) @ GameError::GameAlreadyStarted,
     )]
     pub game: Box<Account<'info, GameState>>,
 
-    #[account(mut)]
-    pub creator: Signer<'info>,
+    // The creator, or any player of a stalled or already cancelling game
+    pub caller: Signer<'info>,
 
-    /// CHECK: game authority PDA - validated in handler
+    /// CHECK: game creator, receives the rent of the vault and game accounts
     #[account(
-        seeds = [crate::constants::GAME_AUTHORITY_SEED.as_ref()],
+        mut,
+        address = game.creator @ GameError::Unauthorized,
+    )]
+    pub creator: UncheckedAccount<'info>,
+
+    /// CHECK: game authority PDA - only required for token games
+    #[account(
+        seeds = [crate::constants::GAME_AUTHORITY_SEED],
         bump,
     )]
-    pub game_authority: UncheckedAccount<'info>,
+    pub game_authority: Option<UncheckedAccount<'info>>,
 
+    // Writable so withheld transfer fees can be harvested into it
     #[account(
+        mut,
         mint::token_program = token_program,
     )]
-    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
 
     #[account(
         mut,
@@ -39,30 +48,115 @@ pub struct CancelGame<'info> {
         token::authority = game_authority,
         token::token_program = token_program,
     )]
-    pub token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
-    pub token_program: Interface<'info, TokenInterface>,
+    // Lamport escrow, only required for games paid in SOL
+    #[account(
+        mut,
+        seeds = [crate::constants::SOL_VAULT_SEED, game.key().as_ref()],
+        bump,
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
 
     pub system_program: Program<'info, System>,
     pub clock: Sysvar<'info, Clock>,
 }
 
+/// Refunds up to `MAX_PLAYERS_PER_BATCH` players per call. Larger games call
+/// this repeatedly; the vault and game account are closed with the last batch,
+/// or by a final call once sponsors have been refunded with `refund_sponsor`.
+/// A stalled start is cancelled the same way once `undelegate_stalled_start`
+/// has returned the delegated player accounts.
 pub fn cancel_game_handler<'c: 'info, 'info>(
     ctx: Context<'_, '_, 'c, 'info, CancelGame<'info>>,
 ) -> Result<()> {
-    let game = &ctx.accounts.game;
+    let game = &mut ctx.accounts.game;
+    let game_key = game.key();
     let clock = &ctx.accounts.clock;
+    let caller = ctx.accounts.caller.key();
+
+    match game.game_status {
+        GameStatus::WaitingForPlayers => {
+            require!(caller == game.creator, GameError::Unauthorized);
+        }
+        GameStatus::Starting => {
+            require!(
+                caller == game.creator || game.players.contains(&caller),
+                GameError::Unauthorized
+            );
+            require!(
+                game.is_start_stalled(clock.unix_timestamp),
+                GameError::StartNotStalled
+            );
+        }
+        _ => {
+            require!(
+                caller == game.creator || game.players.contains(&caller),
+                GameError::Unauthorized
+            );
+        }
+    }
+
+    if game.game_status != GameStatus::Cancelling {
+        // Lock the lobby until every player has been refunded
+        game.game_status = GameStatus::Cancelling;
+
+        emit!(GameCancelled {
+            game: game_key,
+            creator: game.creator,
+            players_count: game.current_players,
+            refund_amount: game.entry_fee,
+            timestamp: clock.unix_timestamp,
+        });
+    }
 
     msg!(
-        "Canceling game {} with {} players",
+        "Canceling game {} with {} players left to refund",
         game.game_id,
         game.current_players
     );
 
-    if game.entry_fee > 0 {
-        let token_mint = &ctx.accounts.token_mint;
-        let token_vault = &ctx.accounts.token_vault;
-        let game_authority = &ctx.accounts.game_authority;
+    // remaining_accounts: [player_state, player_token_account] per player for token games,
+    // [player_state, player_wallet] per player for SOL and free games
+    let batch_size = game
+        .players
+        .len()
+        .min(ctx.remaining_accounts.len() / 2)
+        .min(MAX_PLAYERS_PER_BATCH);
+
+    // Once every player is refunded, later calls only close the game after the sponsors
+    require!(
+        batch_size > 0 || game.players.is_empty(),
+        GameError::MissingPlayerAccount
+    );
+
+    let batch_accounts = &ctx.remaining_accounts[..batch_size * 2];
+    let mut refunded_total: u64 = 0;
+
+    // Token games and sponsored freerolls keep their pool in the token vault
+    let token_accounts = if game.uses_token_vault() {
+        let token_mint = ctx
+            .accounts
+            .token_mint
+            .as_ref()
+            .ok_or(GameError::MissingTokenAccounts)?;
+        let token_vault = ctx
+            .accounts
+            .token_vault
+            .as_ref()
+            .ok_or(GameError::MissingTokenAccounts)?;
+        let token_program = ctx
+            .accounts
+            .token_program
+            .as_ref()
+            .ok_or(GameError::MissingTokenAccounts)?;
+        let game_authority = ctx
+            .accounts
+            .game_authority
+            .as_ref()
+            .ok_or(GameError::MissingTokenAccounts)?;
 
         let (expected_game_authority, game_authority_bump) =
             Pubkey::find_program_address(&[crate::constants::GAME_AUTHORITY_SEED], ctx.program_id);
@@ -81,15 +175,29 @@ pub fn cancel_game_handler<'c: 'info, 'info>(
             GameError::InvalidTokenAccount
         );
 
+        Some((
+            token_mint,
+            token_vault,
+            token_program,
+            game_authority,
+            game_authority_bump,
+        ))
+    } else {
+        None
+    };
+
+    if let Some((token_mint, token_vault, token_program, game_authority, game_authority_bump)) =
+        token_accounts.filter(|_| game.entry_fee > 0)
+    {
         let authority_seeds = &[
             crate::constants::GAME_AUTHORITY_SEED,
             &[game_authority_bump],
         ];
         let signer_seeds = &[&authority_seeds[..]];
 
-        let remaining_accounts_iter = &mut ctx.remaining_accounts.iter();
+        let remaining_accounts_iter = &mut batch_accounts.iter();
 
-        for (idx, player_pubkey) in game.players.iter().enumerate() {
+        for (idx, player_pubkey) in game.players[..batch_size].iter().enumerate() {
             msg!(
                 "Processing refund for player {} (index {})",
                 player_pubkey,
@@ -101,7 +209,7 @@ pub fn cancel_game_handler<'c: 'info, 'info>(
                 .ok_or(GameError::MissingPlayerAccount)?;
 
             let expected_player_state_key = Pubkey::find_program_address(
-                &[b"player", game.key().as_ref(), player_pubkey.as_ref()],
+                &[b"player", game_key.as_ref(), player_pubkey.as_ref()],
                 ctx.program_id,
             )
             .0;
@@ -111,6 +219,12 @@ pub fn cancel_game_handler<'c: 'info, 'info>(
                 GameError::InvalidPlayerAccount
             );
 
+            let entry_deposit =
+                Account::<PlayerState>::try_from(player_state_account)?.entry_deposit;
+            refunded_total = refunded_total
+                .checked_add(entry_deposit)
+                .ok_or(GameError::ArithmeticOverflow)?;
+
             let player_token_account_info = remaining_accounts_iter
                 .next()
                 .ok_or(GameError::MissingPlayerTokenAccount)?;
@@ -135,16 +249,16 @@ pub fn cancel_game_handler<'c: 'info, 'info>(
             };
 
             let transfer_ctx = CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
+                token_program.to_account_info(),
                 transfer_accounts,
                 signer_seeds,
             );
 
-            transfer_checked(transfer_ctx, game.entry_fee, token_mint.decimals)?;
+            transfer_checked(transfer_ctx, entry_deposit, token_mint.decimals)?;
 
             msg!(
                 "Refunded {} tokens to player {}",
-                game.entry_fee,
+                entry_deposit,
                 player_pubkey
             );
 
@@ -158,26 +272,28 @@ pub fn cancel_game_handler<'c: 'info, 'info>(
 
             msg!("Closed player state account for {}", player_pubkey);
         }
-
-        let close_vault_accounts = CloseAccount {
-            account: token_vault.to_account_info(),
-            destination: ctx.accounts.creator.to_account_info(),
-            authority: game_authority.to_account_info(),
+    } else {
+        let sol_vault = if game.uses_sol_vault() {
+            Some(
+                ctx.accounts
+                    .sol_vault
+                    .as_ref()
+                    .ok_or(GameError::MissingSolVault)?,
+            )
+        } else {
+            require!(
+                ctx.accounts.sol_vault.is_none()
+                    && (game.uses_token_vault()
+                        || (ctx.accounts.token_mint.is_none()
+                            && ctx.accounts.token_vault.is_none())),
+                GameError::UnexpectedTokenAccounts
+            );
+            None
         };
 
-        let close_vault_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            close_vault_accounts,
-            signer_seeds,
-        );
-
-        close_account(close_vault_ctx)?;
-
-        msg!("Token vault closed, rent refunded to creator");
-    } else {
-        let remaining_accounts_iter = &mut ctx.remaining_accounts.iter();
+        let remaining_accounts_iter = &mut batch_accounts.iter();
 
-        for (idx, player_pubkey) in game.players.iter().enumerate() {
+        for (idx, player_pubkey) in game.players[..batch_size].iter().enumerate() {
             msg!(
                 "Closing player state for player {} (index {})",
                 player_pubkey,
@@ -189,7 +305,7 @@ pub fn cancel_game_handler<'c: 'info, 'info>(
                 .ok_or(GameError::MissingPlayerAccount)?;
 
             let expected_player_state_key = Pubkey::find_program_address(
-                &[b"player", game.key().as_ref(), player_pubkey.as_ref()],
+                &[b"player", game_key.as_ref(), player_pubkey.as_ref()],
                 ctx.program_id,
             )
             .0;
@@ -199,6 +315,12 @@ pub fn cancel_game_handler<'c: 'info, 'info>(
                 GameError::InvalidPlayerAccount
             );
 
+            let entry_deposit =
+                Account::<PlayerState>::try_from(player_state_account)?.entry_deposit;
+            refunded_total = refunded_total
+                .checked_add(entry_deposit)
+                .ok_or(GameError::ArithmeticOverflow)?;
+
             let recipient_account = remaining_accounts_iter
                 .next()
                 .ok_or(GameError::MissingPlayerAccount)?;
@@ -208,6 +330,22 @@ pub fn cancel_game_handler<'c: 'info, 'info>(
                 GameError::InvalidPlayerAccount
             );
 
+            if let Some(sol_vault) = sol_vault {
+                transfer_from_sol_vault(
+                    &sol_vault.to_account_info(),
+                    recipient_account,
+                    &ctx.accounts.system_program.to_account_info(),
+                    &game_key,
+                    entry_deposit,
+                )?;
+
+                msg!(
+                    "Refunded {} lamports to player {}",
+                    entry_deposit,
+                    player_pubkey
+                );
+            }
+
             let player_state_lamports = player_state_account.lamports();
 
             **player_state_account.try_borrow_mut_lamports()? = 0;
@@ -218,17 +356,90 @@ pub fn cancel_game_handler<'c: 'info, 'info>(
 
             msg!("Closed player state account for {}", player_pubkey);
         }
+
+        match sol_vault {
+            Some(sol_vault) if batch_size == game.players.len() => {
+                // Only the escrow's rent is left once every entry fee is refunded
+                transfer_from_sol_vault(
+                    &sol_vault.to_account_info(),
+                    &ctx.accounts.creator.to_account_info(),
+                    &ctx.accounts.system_program.to_account_info(),
+                    &game_key,
+                    sol_vault.lamports(),
+                )?;
+
+                msg!("SOL vault emptied, rent refunded to creator");
+            }
+            _ => {}
+        }
     }
 
-    emit!(GameCancelled {
-        game: game.key(),
-        creator: game.creator,
-        players_count: game.current_players,
-        refund_amount: game.entry_fee,
-        timestamp: clock.unix_timestamp,
-    });
+    // The vault stays open until sponsors have taken their contributions back
+    let vault_emptied = batch_size == game.players.len() && game.sponsor_count == 0;
+
+    match token_accounts {
+        Some((token_mint, token_vault, token_program, game_authority, game_authority_bump))
+            if vault_emptied =>
+        {
+            // Token-2022 can't close an account holding withheld fees until they are harvested
+            if has_withheld_transfer_fees(&token_vault.to_account_info())? {
+                harvest_withheld_fees(
+                    &token_program.to_account_info(),
+                    &token_mint.to_account_info(),
+                    &token_vault.to_account_info(),
+                )?;
+
+                msg!("Withheld transfer fees harvested to the mint");
+            }
+
+            let authority_seeds = &[
+                crate::constants::GAME_AUTHORITY_SEED,
+                &[game_authority_bump],
+            ];
+            let signer_seeds = &[&authority_seeds[..]];
+
+            let close_vault_accounts = CloseAccount {
+                account: token_vault.to_account_info(),
+                destination: ctx.accounts.creator.to_account_info(),
+                authority: game_authority.to_account_info(),
+            };
 
-    msg!("Game {} successfully cancelled", game.game_id);
+            let close_vault_ctx = CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                close_vault_accounts,
+                signer_seeds,
+            );
+
+            close_account(close_vault_ctx)?;
+
+            msg!("Token vault closed, rent refunded to creator");
+        }
+        _ => {}
+    }
+
+    game.total_prize_pool = game.total_prize_pool.saturating_sub(refunded_total);
+
+    // Refunded players leave the lobby
+    game.players.drain(..batch_size);
+    game.player_eliminated.drain(..batch_size);
+    game.current_players = game.players.len() as u8;
+
+    if game.players.is_empty() && game.sponsor_count > 0 {
+        msg!(
+            "Players refunded, refund the remaining {} sponsors then call cancel_game again",
+            game.sponsor_count
+        );
+    } else if game.players.is_empty() {
+        game.close(ctx.accounts.creator.to_account_info())?;
+
+        msg!("Game {} successfully cancelled", game.game_id);
+    } else {
+        msg!(
+            "Refunded {} players, call cancel_game again for the remaining {}",
+            batch_size,
+            game.current_players
+        );
+    }
 
     Ok(())
 }
@@ -1,6 +1,7 @@
 use crate::constants::GAME_AUTHORITY_SEED;
 use crate::error::GameError;
 use crate::state::*;
+use crate::utils::transfer_from_sol_vault;
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{
     transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
@@ -14,32 +15,36 @@ pub struct ClaimReward<'info> {
         bump = game.bump,
         constraint = game.game_status == GameStatus::Finished @ GameError::GameNotFinished,
         constraint = !game.prize_claimed @ GameError::PrizeAlreadyClaimed,
-        constraint = game.winner.is_some() @ GameError::NoWinnerDeclared,
-        constraint = game.winner.unwrap() == winner.key() @ GameError::NotWinner,
     )]
     pub game: Box<Account<'info, GameState>>,
 
-    /// CHECK: game authority PDA for token transfers
     #[account(
-        seeds = [GAME_AUTHORITY_SEED.as_ref()],
+        seeds = [b"platform", game.config_id.as_ref()],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, PlatformConfig>>,
+
+    /// CHECK: game authority PDA - only required for token games
+    #[account(
+        seeds = [GAME_AUTHORITY_SEED],
         bump,
     )]
-    pub game_authority: UncheckedAccount<'info>,
+    pub game_authority: Option<UncheckedAccount<'info>>,
 
     #[account(
         mint::token_program = token_program,
-        constraint = token_mint.key() == game.token_mint.unwrap() @ GameError::InvalidTokenAccount,
+        constraint = Some(token_mint.key()) == game.token_mint @ GameError::InvalidTokenAccount,
     )]
-    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
 
     #[account(
         mut,
         token::mint = token_mint,
         token::authority = game_authority,
         token::token_program = token_program,
-        constraint = token_vault.key() == game.token_vault.unwrap() @ GameError::InvalidTokenAccount,
+        constraint = Some(token_vault.key()) == game.token_vault @ GameError::InvalidTokenAccount,
     )]
-    pub token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     #[account(
         mut,
@@ -47,12 +52,36 @@ pub struct ClaimReward<'info> {
         token::authority = winner,
         token::token_program = token_program,
     )]
-    pub winner_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub winner_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    // Platform fee destination, owned by the configured fee vault
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = config.fee_vault,
+        token::token_program = token_program,
+    )]
+    pub fee_vault_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    // Lamport escrow, only required for games paid in SOL
+    #[account(
+        mut,
+        seeds = [crate::constants::SOL_VAULT_SEED, game.key().as_ref()],
+        bump,
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
+
+    /// CHECK: platform fee destination for games paid in SOL
+    #[account(mut, address = config.fee_vault @ GameError::InvalidAccount)]
+    pub fee_vault: Option<UncheckedAccount<'info>>,
 
+    // Any player who finished in a paid placement
     #[account(mut)]
     pub winner: Signer<'info>,
 
-    pub token_program: Interface<'info, TokenInterface>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
 
     pub clock: Sysvar<'info, Clock>,
 }
@@ -61,78 +90,178 @@ pub fn claim_reward_handler(ctx: Context<ClaimReward>) -> Result<()> {
     let game = &mut ctx.accounts.game;
     let clock = &ctx.accounts.clock;
 
-    require!(
-        game.entry_fee > 0 && game.total_prize_pool > 0,
-        GameError::NoPrizeToClaim
-    );
+    require!(game.total_prize_pool > 0, GameError::NoPrizeToClaim);
 
-    let prize_amount = game.total_prize_pool;
     let winner_pubkey = ctx.accounts.winner.key();
 
-    let token_mint = &ctx.accounts.token_mint;
-    let token_vault = &ctx.accounts.token_vault;
-    let winner_token_account = &ctx.accounts.winner_token_account;
-    let game_authority = &ctx.accounts.game_authority;
-
-    let (expected_game_authority, game_authority_bump) =
-        Pubkey::find_program_address(&[GAME_AUTHORITY_SEED], ctx.program_id);
+    let placement = game
+        .final_ranking
+        .iter()
+        .position(|&player| player == winner_pubkey)
+        .ok_or(GameError::NotWinner)?;
 
+    require!(placement < game.prize_claims.len(), GameError::NotWinner);
     require!(
-        game_authority.key() == expected_game_authority,
-        GameError::InvalidGameAuthority
+        !game.prize_claims[placement],
+        GameError::PrizeAlreadyClaimed
     );
 
-    require!(
-        winner_token_account.mint == token_mint.key(),
-        GameError::InvalidTokenAccount
-    );
+    let share = game.prize_share(placement)?;
+    require!(share > 0, GameError::NoPrizeToClaim);
 
-    require!(
-        winner_token_account.owner == winner_pubkey,
-        GameError::InvalidTokenAccount
-    );
+    let platform_fee = PlatformConfig::calculate_fee(share, game.fee_basis_points);
+    let prize_amount = share
+        .checked_sub(platform_fee)
+        .ok_or(GameError::ArithmeticUnderflow)?;
 
-    require!(
-        token_vault.mint == token_mint.key(),
-        GameError::InvalidTokenAccount
-    );
+    if game.uses_sol_vault() {
+        let sol_vault = ctx
+            .accounts
+            .sol_vault
+            .as_ref()
+            .ok_or(GameError::MissingSolVault)?;
+        let fee_vault = ctx
+            .accounts
+            .fee_vault
+            .as_ref()
+            .ok_or(GameError::MissingSolVault)?;
+        let system_program = ctx.accounts.system_program.to_account_info();
 
-    require!(
-        token_vault.owner == game_authority.key(),
-        GameError::InvalidTokenAccount
-    );
+        transfer_from_sol_vault(
+            &sol_vault.to_account_info(),
+            &ctx.accounts.winner.to_account_info(),
+            &system_program,
+            &game.key(),
+            prize_amount,
+        )?;
 
-    let game_authority_seeds = &[GAME_AUTHORITY_SEED, &[game_authority_bump]];
-    let signer_seeds = &[&game_authority_seeds[..]];
+        if platform_fee > 0 {
+            transfer_from_sol_vault(
+                &sol_vault.to_account_info(),
+                &fee_vault.to_account_info(),
+                &system_program,
+                &game.key(),
+                platform_fee,
+            )?;
+        }
+    } else {
+        let token_mint = ctx
+            .accounts
+            .token_mint
+            .as_ref()
+            .ok_or(GameError::MissingTokenAccounts)?;
+        let token_vault = ctx
+            .accounts
+            .token_vault
+            .as_ref()
+            .ok_or(GameError::MissingTokenAccounts)?;
+        let winner_token_account = ctx
+            .accounts
+            .winner_token_account
+            .as_ref()
+            .ok_or(GameError::MissingTokenAccounts)?;
+        let fee_vault_token_account = ctx
+            .accounts
+            .fee_vault_token_account
+            .as_ref()
+            .ok_or(GameError::MissingTokenAccounts)?;
+        let token_program = ctx
+            .accounts
+            .token_program
+            .as_ref()
+            .ok_or(GameError::MissingTokenAccounts)?;
+        let game_authority = ctx
+            .accounts
+            .game_authority
+            .as_ref()
+            .ok_or(GameError::MissingTokenAccounts)?;
 
-    let transfer_accounts = TransferChecked {
-        from: token_vault.to_account_info(),
-        mint: token_mint.to_account_info(),
-        to: winner_token_account.to_account_info(),
-        authority: game_authority.to_account_info(),
-    };
+        let (expected_game_authority, game_authority_bump) =
+            Pubkey::find_program_address(&[GAME_AUTHORITY_SEED], ctx.program_id);
 
-    let transfer_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        transfer_accounts,
-        signer_seeds,
-    );
+        require!(
+            game_authority.key() == expected_game_authority,
+            GameError::InvalidGameAuthority
+        );
+
+        require!(
+            winner_token_account.mint == token_mint.key(),
+            GameError::InvalidTokenAccount
+        );
+
+        require!(
+            winner_token_account.owner == winner_pubkey,
+            GameError::InvalidTokenAccount
+        );
+
+        require!(
+            token_vault.mint == token_mint.key(),
+            GameError::InvalidTokenAccount
+        );
+
+        require!(
+            token_vault.owner == game_authority.key(),
+            GameError::InvalidTokenAccount
+        );
+
+        let game_authority_seeds = &[GAME_AUTHORITY_SEED, &[game_authority_bump]];
+        let signer_seeds = &[&game_authority_seeds[..]];
+
+        let transfer_accounts = TransferChecked {
+            from: token_vault.to_account_info(),
+            mint: token_mint.to_account_info(),
+            to: winner_token_account.to_account_info(),
+            authority: game_authority.to_account_info(),
+        };
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            transfer_accounts,
+            signer_seeds,
+        );
+
+        transfer_checked(transfer_ctx, prize_amount, token_mint.decimals)?;
+
+        if platform_fee > 0 {
+            let fee_transfer_accounts = TransferChecked {
+                from: token_vault.to_account_info(),
+                mint: token_mint.to_account_info(),
+                to: fee_vault_token_account.to_account_info(),
+                authority: game_authority.to_account_info(),
+            };
+
+            let fee_transfer_ctx = CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                fee_transfer_accounts,
+                signer_seeds,
+            );
+
+            transfer_checked(fee_transfer_ctx, platform_fee, token_mint.decimals)?;
+        }
+    }
 
-    transfer_checked(transfer_ctx, prize_amount, token_mint.decimals)?;
+    game.prize_claims[placement] = true;
 
-    game.prize_claimed = true;
-    game.total_prize_pool = 0;
+    // Places nobody finished in have nothing left to claim
+    let paid_places = game.payout_bps.len().min(game.final_ranking.len());
+    game.prize_claimed = game.prize_claims[..paid_places]
+        .iter()
+        .all(|&claimed| claimed);
 
     msg!(
-        "🎉 Prize of ${} claimed by winner: {}",
+        "🎉 Prize of ${} claimed by player {} for placement {}",
         prize_amount,
-        winner_pubkey
+        winner_pubkey,
+        placement + 1
     );
+    msg!("Platform fee: ${}", platform_fee);
 
     emit!(PrizeClaimed {
         game: game.key(),
         winner: winner_pubkey,
+        placement: placement as u8,
         prize_amount,
+        platform_fee,
         claimed_at: clock.unix_timestamp,
     });
 
@@ -0,0 +1,323 @@
+use crate::constants::{GAME_AUTHORITY_SEED, SOL_VAULT_SEED, SPONSORSHIP_SEED};
+use crate::error::GameError;
+use crate::state::*;
+use crate::utils::{harvest_withheld_fees, has_withheld_transfer_fees, transfer_from_sol_vault};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+    TransferChecked,
+};
+
+#[derive(Accounts)]
+pub struct ClosePlayerState<'info> {
+    #[account(
+        mut,
+        seeds = [b"player", game.key().as_ref(), wallet.key().as_ref()],
+        bump,
+        close = wallet,
+    )]
+    pub player_state: Box<Account<'info, PlayerState>>,
+
+    /// CHECK: deserialized in the handler, may already be closed
+    pub game: UncheckedAccount<'info>,
+
+    /// CHECK: the player who paid the rent, verified by the player state seeds
+    #[account(mut)]
+    pub wallet: UncheckedAccount<'info>,
+}
+
+/// Returns a player's rent once their game is finished. Claims don't read the
+/// player state, so this doesn't wait for the claim window. Anyone can crank it.
+pub fn close_player_state_handler(ctx: Context<ClosePlayerState>) -> Result<()> {
+    require_finished_or_closed(&ctx.accounts.game)?;
+
+    msg!(
+        "Player state for {} closed, rent returned",
+        ctx.accounts.wallet.key()
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseSponsorship<'info> {
+    #[account(
+        mut,
+        seeds = [SPONSORSHIP_SEED, game.key().as_ref(), sponsor.key().as_ref()],
+        bump = sponsorship.bump,
+        close = sponsor,
+    )]
+    pub sponsorship: Box<Account<'info, Sponsorship>>,
+
+    /// CHECK: deserialized in the handler, may already be closed
+    pub game: UncheckedAccount<'info>,
+
+    /// CHECK: receives the sponsorship rent, verified by the sponsorship seeds
+    #[account(mut)]
+    pub sponsor: UncheckedAccount<'info>,
+}
+
+/// Returns a sponsor's rent once the game they sponsored is finished. The
+/// contribution itself went to the prize pool. Anyone can crank it.
+pub fn close_sponsorship_handler(ctx: Context<CloseSponsorship>) -> Result<()> {
+    require_finished_or_closed(&ctx.accounts.game)?;
+
+    msg!(
+        "Sponsorship of {} closed, rent returned",
+        ctx.accounts.sponsor.key()
+    );
+
+    Ok(())
+}
+
+/// A closed game leaves its player states and sponsorships behind, those can
+/// always be closed
+fn require_finished_or_closed(game_info: &AccountInfo) -> Result<()> {
+    if game_info.data_is_empty() {
+        return Ok(());
+    }
+
+    // Still delegated games are owned by the delegation program and rejected here
+    require_keys_eq!(*game_info.owner, crate::ID, GameError::InvalidAccount);
+    let game = GameState::try_deserialize(&mut &game_info.data.borrow()[..])?;
+
+    require!(
+        game.game_status == GameStatus::Finished,
+        GameError::GameNotFinished
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseGame<'info> {
+    #[account(
+        mut,
+        seeds = [b"game", game.config_id.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump,
+        constraint = game.game_status == GameStatus::Finished @ GameError::GameNotFinished,
+        close = creator,
+    )]
+    pub game: Box<Account<'info, GameState>>,
+
+    /// CHECK: paid the game and vault rent, receives it back
+    #[account(mut, address = game.creator @ GameError::Unauthorized)]
+    pub creator: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"platform", game.config_id.as_ref()],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, PlatformConfig>>,
+
+    /// CHECK: game authority PDA - only required for token games
+    #[account(
+        seeds = [GAME_AUTHORITY_SEED],
+        bump,
+    )]
+    pub game_authority: Option<UncheckedAccount<'info>>,
+
+    // Writable so withheld transfer fees can be harvested into it
+    #[account(
+        mut,
+        mint::token_program = token_program,
+        constraint = Some(token_mint.key()) == game.token_mint @ GameError::InvalidTokenAccount,
+    )]
+    pub token_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = game_authority,
+        token::token_program = token_program,
+        constraint = Some(token_vault.key()) == game.token_vault @ GameError::InvalidTokenAccount,
+    )]
+    pub token_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    // Receives unclaimed prizes, only required when the vault isn't empty
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = config.fee_vault,
+        token::token_program = token_program,
+    )]
+    pub fee_vault_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    // Lamport escrow, only required for games paid in SOL
+    #[account(
+        mut,
+        seeds = [SOL_VAULT_SEED, game.key().as_ref()],
+        bump,
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
+
+    /// CHECK: receives unclaimed prizes for games paid in SOL
+    #[account(mut, address = config.fee_vault @ GameError::InvalidAccount)]
+    pub fee_vault: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+
+    pub clock: Sysvar<'info, Clock>,
+}
+
+/// Closes a finished game and its vault once every prize is claimed or the
+/// claim window has expired. Prizes still unclaimed go to the platform fee
+/// vault, and the rent goes back to the creator. Anyone can crank it.
+pub fn close_game_handler(ctx: Context<CloseGame>) -> Result<()> {
+    let game = &ctx.accounts.game;
+    let game_key = game.key();
+    let clock = &ctx.accounts.clock;
+
+    require!(
+        game.claims_settled(clock.unix_timestamp),
+        GameError::ClaimWindowOpen
+    );
+
+    let mut unclaimed_amount: u64 = 0;
+
+    if game.uses_token_vault() {
+        let token_mint = ctx
+            .accounts
+            .token_mint
+            .as_ref()
+            .ok_or(GameError::MissingTokenAccounts)?;
+        let token_vault = ctx
+            .accounts
+            .token_vault
+            .as_ref()
+            .ok_or(GameError::MissingTokenAccounts)?;
+        let token_program = ctx
+            .accounts
+            .token_program
+            .as_ref()
+            .ok_or(GameError::MissingTokenAccounts)?;
+        let game_authority = ctx
+            .accounts
+            .game_authority
+            .as_ref()
+            .ok_or(GameError::MissingTokenAccounts)?;
+
+        let (expected_game_authority, game_authority_bump) =
+            Pubkey::find_program_address(&[GAME_AUTHORITY_SEED], ctx.program_id);
+
+        require!(
+            game_authority.key() == expected_game_authority,
+            GameError::InvalidGameAuthority
+        );
+
+        let authority_seeds = &[GAME_AUTHORITY_SEED, &[game_authority_bump]];
+        let signer_seeds = &[&authority_seeds[..]];
+
+        unclaimed_amount = token_vault.amount;
+
+        if unclaimed_amount > 0 {
+            let fee_vault_token_account = ctx
+                .accounts
+                .fee_vault_token_account
+                .as_ref()
+                .ok_or(GameError::MissingTokenAccounts)?;
+
+            let transfer_accounts = TransferChecked {
+                from: token_vault.to_account_info(),
+                mint: token_mint.to_account_info(),
+                to: fee_vault_token_account.to_account_info(),
+                authority: game_authority.to_account_info(),
+            };
+
+            let transfer_ctx = CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                transfer_accounts,
+                signer_seeds,
+            );
+
+            transfer_checked(transfer_ctx, unclaimed_amount, token_mint.decimals)?;
+
+            msg!(
+                "Swept {} unclaimed tokens to the fee vault",
+                unclaimed_amount
+            );
+        }
+
+        // Token-2022 can't close an account holding withheld fees until they are harvested
+        if has_withheld_transfer_fees(&token_vault.to_account_info())? {
+            harvest_withheld_fees(
+                &token_program.to_account_info(),
+                &token_mint.to_account_info(),
+                &token_vault.to_account_info(),
+            )?;
+
+            msg!("Withheld transfer fees harvested to the mint");
+        }
+
+        let close_vault_accounts = CloseAccount {
+            account: token_vault.to_account_info(),
+            destination: ctx.accounts.creator.to_account_info(),
+            authority: game_authority.to_account_info(),
+        };
+
+        let close_vault_ctx = CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            close_vault_accounts,
+            signer_seeds,
+        );
+
+        close_account(close_vault_ctx)?;
+
+        msg!("Token vault closed, rent refunded to creator");
+    } else if game.uses_sol_vault() {
+        let sol_vault = ctx
+            .accounts
+            .sol_vault
+            .as_ref()
+            .ok_or(GameError::MissingSolVault)?;
+        let system_program = ctx.accounts.system_program.to_account_info();
+
+        // Anything above the creator's rent top-up is prize money nobody claimed
+        let rent = Rent::get()?.minimum_balance(0);
+        unclaimed_amount = sol_vault.lamports().saturating_sub(rent);
+
+        if unclaimed_amount > 0 {
+            let fee_vault = ctx
+                .accounts
+                .fee_vault
+                .as_ref()
+                .ok_or(GameError::MissingSolVault)?;
+
+            transfer_from_sol_vault(
+                &sol_vault.to_account_info(),
+                &fee_vault.to_account_info(),
+                &system_program,
+                &game_key,
+                unclaimed_amount,
+            )?;
+
+            msg!(
+                "Swept {} unclaimed lamports to the fee vault",
+                unclaimed_amount
+            );
+        }
+
+        transfer_from_sol_vault(
+            &sol_vault.to_account_info(),
+            &ctx.accounts.creator.to_account_info(),
+            &system_program,
+            &game_key,
+            sol_vault.lamports(),
+        )?;
+
+        msg!("SOL vault emptied, rent refunded to creator");
+    }
+
+    emit!(GameClosed {
+        game: game_key,
+        creator: game.creator,
+        unclaimed_amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Game {} closed", game.game_id);
+
+    Ok(())
+}
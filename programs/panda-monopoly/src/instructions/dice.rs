@@ -1,6 +1,9 @@
 use crate::error::GameError;
-use crate::{constants::*, random_two_u8_with_range, xorshift64star, ID};
-use crate::{force_end_turn_util, send_player_to_jail_and_end_turn, state::*};
+use crate::{
+    commit_reveal_seed, constants::*, find_slot_hash, random_two_u8_with_range, xorshift64star,
+    SlotHashLookup, ID,
+};
+use crate::{force_end_turn_util, send_player_to_jail_and_end_turn, state::*, visit_free_parking};
 use anchor_lang::prelude::*;
 use ephemeral_vrf_sdk::anchor::vrf;
 use ephemeral_vrf_sdk::instructions::create_request_randomness_ix;
@@ -20,7 +23,8 @@ pub struct RollDice<'info> {
     #[account(
         mut,
         seeds = [b"player", game.key().as_ref(), player.key().as_ref()],
-        bump
+        bump,
+        constraint = !player_state.needs_bankruptcy_check @ GameError::DebtOutstanding
     )]
     pub player_state: Box<Account<'info, PlayerState>>,
 
@@ -33,16 +37,20 @@ pub struct RollDice<'info> {
     #[account(mut, address = ephemeral_vrf_sdk::consts::DEFAULT_EPHEMERAL_QUEUE)]
     pub oracle_queue: AccountInfo<'info>,
 
-    /// CHECK: This is the recent blockhashes sysvar
-    #[account(address = anchor_lang::solana_program::sysvar::recent_blockhashes::ID)]
-    pub recent_blockhashes: UncheckedAccount<'info>,
+    /// CHECK: This is the slot hashes sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
 }
 
+/// Without VRF the roll comes from the player's next hash chain value, `reveal`,
+/// mixed with the hash of the slot recorded at the previous action. `use_vrf`
+/// must match the game's randomness source. `dice_roll` fixes the roll in local builds.
 pub fn roll_dice_handler(
     ctx: Context<RollDice>,
     use_vrf: bool,
     client_seed: u8,
     dice_roll: Option<[u8; 2]>,
+    reveal: Option<[u8; 32]>,
 ) -> Result<()> {
     let game = &mut ctx.accounts.game;
     let player_state = &mut ctx.accounts.player_state;
@@ -63,6 +71,11 @@ pub fn roll_dice_handler(
         return Err(GameError::AlreadyRolledDice.into());
     }
 
+    require!(use_vrf == game.use_vrf, GameError::RandomnessModeMismatch);
+
+    #[cfg(not(feature = "local"))]
+    require!(dice_roll.is_none(), GameError::RandomnessOverrideNotAllowed);
+
     player_state.record_action(clock);
 
     if dice_roll.is_none() {
@@ -104,21 +117,21 @@ pub fn roll_dice_handler(
             ctx.accounts
                 .invoke_signed_vrf(&ctx.accounts.player.to_account_info(), &ix)?;
         } else {
+            // The reveal is checked against the player's commitment before it is used
+            let reveal = reveal.ok_or(GameError::InvalidRandomnessReveal)?;
+            let seed = commit_reveal_seed(
+                game,
+                player_state,
+                &ctx.accounts.slot_hashes,
+                reveal,
+                clock.slot,
+            )?;
+            let dice_roll = dice_from_seed(seed);
+
             if player_state.in_jail {
-                return handle_jail_dice_roll_without_vrf(
-                    game,
-                    player_state,
-                    clock,
-                    &ctx.accounts.recent_blockhashes,
-                );
+                return handle_jail_dice_roll(game, player_state, clock, dice_roll);
             }
 
-            // Generate secure random dice roll using recent blockhash
-            let dice_roll = dice_roll.unwrap_or_else(|| {
-                generate_fake_dice_roll(&ctx.accounts.recent_blockhashes, clock.unix_timestamp)
-                    .unwrap()
-            });
-
             player_state.last_dice_roll = dice_roll;
             game.turn_started_at = clock.unix_timestamp;
 
@@ -162,12 +175,13 @@ pub fn roll_dice_handler(
 
             // Check if player passed GO
             if new_position < old_position {
-                player_state.cash_balance += GO_SALARY as u64;
+                let go_salary = game.rules.go_salary_for(new_position);
+                player_state.cash_balance += go_salary;
 
                 emit!(PlayerPassedGo {
                     player: player_pubkey,
                     game: game.key(),
-                    salary_collected: GO_SALARY as u64,
+                    salary_collected: go_salary,
                     new_position,
                     timestamp: clock.unix_timestamp,
                 });
@@ -175,7 +189,7 @@ pub fn roll_dice_handler(
                 msg!(
                     "Player {} passed GO and collected ${}",
                     player_pubkey,
-                    GO_SALARY
+                    go_salary
                 );
             }
 
@@ -194,7 +208,7 @@ pub fn roll_dice_handler(
         }
     } else {
         // for test - handle the dice roll directly
-        let dice_roll = dice_roll.unwrap();
+        let dice_roll = dice_roll.ok_or(GameError::InvalidDiceRoll)?;
 
         player_state.last_dice_roll = dice_roll;
         game.turn_started_at = clock.unix_timestamp;
@@ -239,12 +253,13 @@ pub fn roll_dice_handler(
 
         // Check if player passed GO
         if new_position < old_position {
-            player_state.cash_balance += GO_SALARY as u64;
+            let go_salary = game.rules.go_salary_for(new_position);
+            player_state.cash_balance += go_salary;
 
             emit!(PlayerPassedGo {
                 player: player_pubkey,
                 game: game.key(),
-                salary_collected: GO_SALARY as u64,
+                salary_collected: go_salary,
                 new_position,
                 timestamp: clock.unix_timestamp,
             });
@@ -252,7 +267,7 @@ pub fn roll_dice_handler(
             msg!(
                 "Player {} passed GO and collected ${}",
                 player_pubkey,
-                GO_SALARY
+                go_salary
             );
         }
 
@@ -273,6 +288,113 @@ pub fn roll_dice_handler(
     Ok(())
 }
 
+#[derive(Accounts)]
+pub struct CommitRandomness<'info> {
+    #[account(
+        seeds = [b"game", game.config_id.as_ref(), &game.game_id.to_le_bytes().as_ref()],
+        bump = game.bump,
+        constraint = matches!(game.game_status, GameStatus::WaitingForPlayers | GameStatus::InProgress) @ GameError::GameAlreadyStarted,
+        constraint = !game.use_vrf @ GameError::RandomnessModeMismatch
+    )]
+    pub game: Box<Account<'info, GameState>>,
+
+    #[account(
+        mut,
+        seeds = [b"player", game.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub player_state: Box<Account<'info, PlayerState>>,
+
+    pub player: Signer<'info>,
+}
+
+/// Commits the head of a hash chain before a commit-reveal game starts. Each
+/// roll and card draw reveals the value that hashes to the current head, so
+/// players should build a chain longer than the number of draws they expect.
+/// During play a new chain replaces the current one between the player's
+/// turns, against a reveal of the current head while it still has values left.
+pub fn commit_randomness_handler(
+    ctx: Context<CommitRandomness>,
+    commitment: [u8; 32],
+    reveal: Option<[u8; 32]>,
+) -> Result<()> {
+    let game = &ctx.accounts.game;
+    let player_state = &mut ctx.accounts.player_state;
+
+    if game.game_status == GameStatus::InProgress {
+        require!(
+            game.players.contains(&player_state.wallet),
+            GameError::PlayerNotFound
+        );
+        // On their own turn the player already knows the anchored slot hash
+        require!(
+            game.players.get(game.current_turn as usize) != Some(&player_state.wallet),
+            GameError::RecommitDuringOwnTurn
+        );
+
+        let reveal = reveal.ok_or(GameError::InvalidRandomnessReveal)?;
+        player_state.reveal_randomness(reveal)?;
+    }
+
+    player_state.randomness_commitment = Some(commitment);
+
+    msg!("Player {} committed randomness", player_state.wallet);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RefreshRandomnessSlot<'info> {
+    #[account(
+        mut,
+        seeds = [b"game", game.config_id.as_ref(), &game.game_id.to_le_bytes().as_ref()],
+        bump = game.bump,
+        constraint = game.game_status == GameStatus::InProgress @ GameError::GameNotInProgress,
+        constraint = !game.use_vrf @ GameError::RandomnessModeMismatch
+    )]
+    pub game: Box<Account<'info, GameState>>,
+
+    pub player: Signer<'info>,
+
+    /// CHECK: This is the slot hashes sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+}
+
+/// Anchors the current player's next reveal to the current slot once the
+/// anchored slot hash has aged out of SlotHashes, so a slow player can still
+/// roll or draw. The turn and its timer are left as they are.
+pub fn refresh_randomness_slot_handler(ctx: Context<RefreshRandomnessSlot>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let player_pubkey = ctx.accounts.player.key();
+    let clock = &ctx.accounts.clock;
+
+    require!(
+        game.players.get(game.current_turn as usize) == Some(&player_pubkey),
+        GameError::NotPlayerTurn
+    );
+
+    require!(
+        find_slot_hash(
+            &ctx.accounts.slot_hashes.try_borrow_data()?,
+            game.randomness_slot
+        ) == SlotHashLookup::Expired,
+        GameError::RandomnessSlotNotExpired
+    );
+
+    game.randomness_slot = clock.slot;
+
+    msg!(
+        "Player {} re-anchored randomness to slot {}",
+        player_pubkey,
+        clock.slot
+    );
+
+    Ok(())
+}
+
 #[derive(Accounts)]
 pub struct CallbackRollDiceCtx<'info> {
     /// This check ensure that the vrf_program_identity (which is a PDA) is a singer
@@ -355,12 +477,13 @@ pub fn callback_roll_dice(ctx: Context<CallbackRollDiceCtx>, randomness: [u8; 32
 
     // Check if player passed GO
     if new_position < old_position {
-        player_state.cash_balance += GO_SALARY as u64;
+        let go_salary = game.rules.go_salary_for(new_position);
+        player_state.cash_balance += go_salary;
 
         emit!(PlayerPassedGo {
             player: player_pubkey,
             game: game.key(),
-            salary_collected: GO_SALARY as u64,
+            salary_collected: go_salary,
             new_position,
             timestamp: clock.unix_timestamp,
         });
@@ -368,7 +491,7 @@ pub fn callback_roll_dice(ctx: Context<CallbackRollDiceCtx>, randomness: [u8; 32
         msg!(
             "Player {} passed GO and collected ${}",
             player_pubkey,
-            GO_SALARY
+            go_salary
         );
     }
 
@@ -399,7 +522,7 @@ fn handle_jail_dice_roll(
     player_state.has_rolled_dice = true;
 
     let is_doubles = dice_roll[0] == dice_roll[1];
-    let mut player_escaped = false;
+    let player_escaped;
 
     if is_doubles {
         player_state.in_jail = false;
@@ -408,10 +531,12 @@ fn handle_jail_dice_roll(
         player_escaped = true;
 
         msg!("Player rolled doubles and escaped jail!");
-    } else if player_state.jail_turns >= MAX_JAIL_TURNS {
+    } else if player_state.jail_turns >= game.rules.max_jail_turns {
         // Must pay fine after max turns
-        if player_state.cash_balance >= JAIL_FINE as u64 {
-            player_state.cash_balance -= JAIL_FINE as u64;
+        if player_state.cash_balance >= game.rules.jail_fine {
+            let jail_fine = game.rules.jail_fine;
+            player_state.cash_balance -= jail_fine;
+            game.add_to_free_parking(jail_fine);
             player_state.in_jail = false;
             player_state.jail_turns = 0;
             player_escaped = true;
@@ -430,7 +555,11 @@ fn handle_jail_dice_roll(
             return Ok(());
         }
     } else {
-        msg!("Player remains in jail. Turn {}/3", player_state.jail_turns);
+        msg!(
+            "Player remains in jail. Turn {}/{}",
+            player_state.jail_turns,
+            game.rules.max_jail_turns
+        );
         force_end_turn_util(game, player_state, clock);
         return Ok(());
     }
@@ -442,17 +571,18 @@ fn handle_jail_dice_roll(
         let new_position = (old_position + dice_sum) % BOARD_SIZE;
 
         if new_position < old_position {
-            player_state.cash_balance += GO_SALARY as u64;
+            let go_salary = game.rules.go_salary_for(new_position);
+            player_state.cash_balance += go_salary;
 
             emit!(PlayerPassedGo {
                 player: player_state.wallet,
                 game: game.key(),
-                salary_collected: GO_SALARY as u64,
+                salary_collected: go_salary,
                 new_position,
                 timestamp: clock.unix_timestamp,
             });
 
-            msg!("Player passed GO and collected ${}", GO_SALARY);
+            msg!("Player passed GO and collected ${}", go_salary);
         }
 
         player_state.position = new_position;
@@ -495,7 +625,6 @@ fn handle_space_landing(
             // Special space
             handle_special_space(game, player_state, position, clock)?;
         }
-        _ => {}
     }
 
     Ok(())
@@ -511,8 +640,10 @@ fn handle_special_space(
         GO_POSITION => {
             // Already handled in movement
         }
-        JAIL_POSITION => {
-            // Just visiting jail, no action needed
+        FESTIVAL_POSITION => {
+            // Just visiting jail doubles as the festival square
+            player_state.needs_special_space_action = true;
+            player_state.pending_special_space_position = Some(position);
         }
         GO_TO_JAIL_POSITION => {
             send_player_to_jail_and_end_turn(game, player_state, clock);
@@ -528,129 +659,23 @@ fn handle_special_space(
             player_state.needs_community_chest_card = true;
         }
         FREE_PARKING_POSITION => {
-            // Free parking - no action
+            // Beach resort bonus, plus the jackpot when that house rule is on
+            let game_key = game.key();
+            visit_free_parking(game, game_key, player_state, clock)?;
         }
         _ => {}
     }
     Ok(())
 }
 
-// --- for test
-fn handle_jail_dice_roll_without_vrf(
-    game: &mut Box<Account<'_, GameState>>,
-    player_state: &mut Box<Account<'_, PlayerState>>,
-    clock: &Sysvar<Clock>,
-    recent_blockhashes: &UncheckedAccount,
-) -> Result<()> {
-    player_state.jail_turns += 1;
-
-    let dice_roll = generate_fake_dice_roll(recent_blockhashes, clock.unix_timestamp)?;
-    player_state.last_dice_roll = dice_roll;
-    player_state.has_rolled_dice = true;
-
-    let is_doubles = dice_roll[0] == dice_roll[1];
-    let mut player_escaped = false;
-
-    if is_doubles {
-        player_state.in_jail = false;
-        player_state.jail_turns = 0;
-        player_state.doubles_count = 0;
-        player_escaped = true;
-
-        msg!("Player rolled doubles and escaped jail!");
-    } else if player_state.jail_turns >= MAX_JAIL_TURNS {
-        // Must pay fine after max turns
-        if player_state.cash_balance >= JAIL_FINE as u64 {
-            player_state.cash_balance -= JAIL_FINE as u64;
-            player_state.in_jail = false;
-            player_state.jail_turns = 0;
-            player_escaped = true;
-
-            msg!("Player paid jail fine and is released!");
-        } else {
-            // Player can't afford jail fine - declare bankruptcy and end turn
-            player_state.cash_balance = 0;
-            player_state.is_bankrupt = true;
-            player_state.in_jail = false; // Remove from jail since they're bankrupt
-            player_state.jail_turns = 0;
-
-            force_end_turn_util(game, player_state, clock);
-
-            msg!("Player cannot afford jail fine and is declared bankrupt. Turn ended automatically.");
-            return Ok(());
-        }
-    } else {
-        msg!("Player remains in jail. Turn {}/3", player_state.jail_turns);
-        force_end_turn_util(game, player_state, clock);
-        return Ok(());
-    }
-
-    if player_escaped {
-        // Calculate movement
-        let dice_sum = dice_roll[0] + dice_roll[1];
-        let old_position = player_state.position;
-        let new_position = (old_position + dice_sum) % BOARD_SIZE;
-
-        if new_position < old_position {
-            player_state.cash_balance += GO_SALARY as u64;
-
-            emit!(PlayerPassedGo {
-                player: player_state.wallet,
-                game: game.key(),
-                salary_collected: GO_SALARY as u64,
-                new_position,
-                timestamp: clock.unix_timestamp,
-            });
-
-            msg!("Player passed GO and collected ${}", GO_SALARY);
-        }
-
-        player_state.position = new_position;
-
-        handle_space_landing(game, player_state, new_position, clock)?;
-
-        msg!(
-            "Player escaped jail and moved from {} to {}",
-            old_position,
-            new_position
-        );
-    }
-
-    game.turn_started_at = clock.unix_timestamp;
-    Ok(())
-}
-
-fn generate_fake_dice_roll(
-    recent_blockhashes: &UncheckedAccount,
-    timestamp: i64,
-) -> Result<[u8; 2]> {
-    // Get recent blockhash data for randomness
-    let data = recent_blockhashes.try_borrow_data()?;
-
-    // Create a more diverse seed by combining multiple entropy sources
-    let mut seed = 0u64;
-
-    // Use blockhash data (take 8 bytes and convert to u64)
-    if data.len() >= 8 {
-        seed ^= u64::from_le_bytes([
-            data[0], data[1], data[2], data[3], data[4], data[5], data[6], data[7],
-        ]);
-    }
-
-    // Mix in timestamp
-    seed ^= timestamp as u64;
-
-    // Mix in more blockhash data from different positions if available
-    if data.len() >= 16 {
-        seed ^= u64::from_le_bytes([
-            data[8], data[9], data[10], data[11], data[12], data[13], data[14], data[15],
-        ]);
-    }
-
+/// Two dice from a commit-reveal seed
+fn dice_from_seed(seed: u64) -> [u8; 2] {
     // Ensure seed is never zero (xorshift doesn't work with 0)
-    if seed == 0 {
-        seed = 0x123456789ABCDEF0u64;
-    }
+    let seed = if seed == 0 {
+        0x123456789ABCDEF0u64
+    } else {
+        seed
+    };
 
     // Generate first random number for dice1
     let rand1 = xorshift64star(seed);
@@ -661,5 +686,5 @@ fn generate_fake_dice_roll(
     let dice1 = ((rand1 % 6) + 1) as u8;
     let dice2 = ((rand2 % 6) + 1) as u8;
 
-    Ok([dice1, dice2])
+    [dice1, dice2]
 }
@@ -27,6 +27,9 @@ pub fn end_game_handler<'c: 'info, 'info>(
         let game = &mut ctx.accounts.game;
         let clock = &ctx.accounts.clock;
 
+        // Deserialize each PlayerState once, lookups below are per player
+        let player_states = load_player_states(ctx.remaining_accounts, &game.key());
+
         // Prevent double-ending
         require!(
             game.game_status != GameStatus::Finished,
@@ -40,6 +43,7 @@ pub fn end_game_handler<'c: 'info, 'info>(
         let end_reason: GameEndReason;
         let mut winner_pubkey: Option<Pubkey> = None;
         let mut winner_net_worth: Option<u64> = None;
+        let survivors: Vec<Pubkey>;
 
         if game.check_bankruptcy_end_condition() {
             end_reason = GameEndReason::BankruptcyVictory;
@@ -49,11 +53,7 @@ pub fn end_game_handler<'c: 'info, 'info>(
                 winner_pubkey = Some(winner);
 
                 // Try to get winner's complete net worth from remaining accounts
-                if let Some(player_state) = find_player_state_in_remaining_accounts(
-                    &ctx.remaining_accounts,
-                    &game.key(),
-                    &winner,
-                )? {
+                if let Some(player_state) = find_player_state(&player_states, &winner) {
                     let property_value = game.calculate_player_net_worth(&winner)?;
                     let total_net_worth = property_value
                         .checked_add(player_state.cash_balance)
@@ -69,6 +69,8 @@ pub fn end_game_handler<'c: 'info, 'info>(
             } else {
                 msg!("Game ended: No remaining players");
             }
+
+            survivors = active_players;
         } else if game.check_time_end_condition(clock.unix_timestamp) {
             end_reason = GameEndReason::TimeLimit;
 
@@ -78,28 +80,27 @@ pub fn end_game_handler<'c: 'info, 'info>(
 
             let mut best_player: Option<Pubkey> = None;
             let mut best_net_worth: u64 = 0;
+            let mut standings: Vec<(Pubkey, u64)> = Vec::with_capacity(active_players.len());
 
             // Calculate complete net worth for each player
             for player in active_players.iter() {
                 let property_value = game.calculate_player_net_worth(player)?;
 
                 // Try to find player's cash balance from remaining accounts
-                let total_net_worth = if let Some(player_state) =
-                    find_player_state_in_remaining_accounts(
-                        &ctx.remaining_accounts,
-                        &game.key(),
-                        player,
-                    )? {
-                    property_value
-                        .checked_add(player_state.cash_balance)
-                        .ok_or(GameError::ArithmeticOverflow)?
-                } else {
-                    // If PlayerState not provided, use property value only
-                    property_value
-                };
+                let total_net_worth =
+                    if let Some(player_state) = find_player_state(&player_states, player) {
+                        property_value
+                            .checked_add(player_state.cash_balance)
+                            .ok_or(GameError::ArithmeticOverflow)?
+                    } else {
+                        // If PlayerState not provided, use property value only
+                        property_value
+                    };
 
                 msg!("Player {} total net worth: ${}", player, total_net_worth);
 
+                standings.push((*player, total_net_worth));
+
                 if total_net_worth > best_net_worth {
                     best_net_worth = total_net_worth;
                     best_player = Some(*player);
@@ -109,6 +110,10 @@ pub fn end_game_handler<'c: 'info, 'info>(
             winner_pubkey = best_player;
             winner_net_worth = Some(best_net_worth);
 
+            // Richest first, ties keep seating order
+            standings.sort_by_key(|&(_, net_worth)| std::cmp::Reverse(net_worth));
+            survivors = standings.iter().map(|(player, _)| *player).collect();
+
             msg!(
                 "🏆 Game ended: Time limit reached. Winner: {:?} with net worth: ${}",
                 winner_pubkey,
@@ -119,75 +124,141 @@ pub fn end_game_handler<'c: 'info, 'info>(
             return Err(GameError::GameCannotEnd.into());
         }
 
+        // An unclaimed Free Parking jackpot belongs to no player, return it to the bank
+        game.bank_balance = game.bank_balance.saturating_add(game.free_parking_pool);
+        game.free_parking_pool = 0;
+
         // Update game state
         game.game_status = GameStatus::Finished;
         game.winner = winner_pubkey;
         game.end_reason = Some(end_reason);
         game.ended_at = Some(clock.unix_timestamp);
+        game.record_final_ranking(&survivors);
 
         emit!(GameEnded {
             game: game.key(),
             winner: winner_pubkey,
             reason: end_reason,
             winner_net_worth,
+            ranking: game.final_ranking.clone(),
             ended_at: clock.unix_timestamp,
         });
 
         msg!("✅ Game {} has ended successfully", game.game_id);
     }
 
-    // undelegate players
-    {
-        let game = &ctx.accounts.game;
-        let remaining_accounts_iter = &mut ctx.remaining_accounts.iter();
-        let players = &game.players;
-        let mut accounts: Vec<&AccountInfo<'info>> = vec![];
-
-        // delegate player accounts
-        for _player_pubkey in players.iter() {
-            let player_account = remaining_accounts_iter
-                .next()
-                .ok_or(GameError::InvalidAccount)?;
-
-            accounts.push(player_account);
-            player_account.exit(&crate::ID)?;
-            // commit_and_undelegate_accounts(
-            //     &ctx.accounts.caller,
-            //     vec![&player_account.to_account_info()],
-            //     &ctx.accounts.magic_context,
-            //     &ctx.accounts.magic_program,
-            // )?;
-
-            // msg!("Player {} undelegated", player_pubkey);
-        }
+    // Hand the game and player accounts back to the base layer for claims
+    commit_and_undelegate_game(
+        &ctx.accounts.game,
+        &ctx.accounts.caller,
+        ctx.remaining_accounts,
+        &ctx.accounts.magic_context,
+        &ctx.accounts.magic_program,
+        ctx.program_id,
+    )
+}
 
-        let acc = &game.to_account_info();
-        accounts.push(acc);
+#[commit]
+#[derive(Accounts)]
+pub struct FinalizeGame<'info> {
+    #[account(
+        mut,
+        seeds = [b"game", game.config_id.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump,
+        constraint = game.game_status == GameStatus::Finished @ GameError::GameNotFinished,
+    )]
+    pub game: Box<Account<'info, GameState>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // remaining_accounts: PlayerState for every player, in seating order
+}
+
+/// Undelegates a game that finished inside another instruction, such as a
+/// timeout bankruptcy. Anyone can call this on the ER once the game is over.
+pub fn finalize_game_handler<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, FinalizeGame<'info>>,
+) -> Result<()> {
+    commit_and_undelegate_game(
+        &ctx.accounts.game,
+        &ctx.accounts.payer,
+        ctx.remaining_accounts,
+        &ctx.accounts.magic_context,
+        &ctx.accounts.magic_program,
+        ctx.program_id,
+    )?;
 
-        game.exit(&crate::ID)?;
-        commit_and_undelegate_accounts(
-            &ctx.accounts.caller,
-            accounts,
-            &ctx.accounts.magic_context,
-            &ctx.accounts.magic_program,
-        )?;
+    msg!("Game {} finalized", ctx.accounts.game.game_id);
+
+    Ok(())
+}
+
+/// Commits the game and every PlayerState, eliminated players included, and
+/// ends their delegation in one schedule
+fn commit_and_undelegate_game<'info>(
+    game: &Account<'info, GameState>,
+    payer: &AccountInfo<'info>,
+    player_accounts: &[AccountInfo<'info>],
+    magic_context: &AccountInfo<'info>,
+    magic_program: &AccountInfo<'info>,
+    program_id: &Pubkey,
+) -> Result<()> {
+    let game_key = game.key();
+    let game_info = game.to_account_info();
+
+    require!(
+        player_accounts.len() >= game.players.len(),
+        GameError::MissingPlayerAccount
+    );
+
+    let mut accounts: Vec<&AccountInfo<'info>> = Vec::with_capacity(game.players.len() + 1);
+
+    for (player_pubkey, player_account) in game.players.iter().zip(player_accounts) {
+        let expected_player_state_key = Pubkey::find_program_address(
+            &[b"player", game_key.as_ref(), player_pubkey.as_ref()],
+            program_id,
+        )
+        .0;
+
+        require!(
+            player_account.key() == expected_player_state_key,
+            GameError::InvalidPlayerAccount
+        );
+
+        accounts.push(player_account);
     }
 
+    accounts.push(&game_info);
+
+    game.exit(&crate::ID)?;
+    commit_and_undelegate_accounts(payer, accounts, magic_context, magic_program)?;
+
+    msg!(
+        "Game {} and {} players undelegated",
+        game.game_id,
+        game.players.len()
+    );
+
     Ok(())
 }
 
-fn find_player_state_in_remaining_accounts<'c: 'info, 'info>(
+fn load_player_states<'c: 'info, 'info>(
     remaining_accounts: &'c [AccountInfo<'info>],
     game_key: &Pubkey,
+) -> Vec<Account<'c, PlayerState>> {
+    remaining_accounts
+        .iter()
+        .filter_map(|account_info| Account::<PlayerState>::try_from(account_info).ok())
+        // Verify it belongs to the correct game
+        .filter(|player_state| player_state.game == *game_key)
+        .collect()
+}
+
+fn find_player_state<'a, 'c>(
+    player_states: &'a [Account<'c, PlayerState>],
     player_key: &Pubkey,
-) -> Result<Option<Account<'c, PlayerState>>> {
-    for account_info in remaining_accounts {
-        if let Ok(player_state) = Account::<PlayerState>::try_from(account_info) {
-            // Verify it belongs to the correct game and player
-            if player_state.game == *game_key && player_state.wallet == *player_key {
-                return Ok(Some(player_state));
-            }
-        }
-    }
-    Ok(None)
+) -> Option<&'a Account<'c, PlayerState>> {
+    player_states
+        .iter()
+        .find(|player_state| player_state.wallet == *player_key)
 }
@@ -1,5 +1,6 @@
 use crate::error::GameError;
 use crate::state::*;
+use crate::tick_festival_boost;
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -65,6 +66,16 @@ pub fn end_turn_handler(ctx: Context<EndTurn>) -> Result<()> {
         return Err(GameError::MustDeclareBankruptcy.into());
     }
 
+    // Taxes can't be skipped, hosting a festival is optional
+    if player_state.has_pending_tax() {
+        return Err(GameError::MustHandleSpecialSpace.into());
+    }
+
+    // Declined property must be settled before play continues
+    if game.has_active_auction() {
+        return Err(GameError::AuctionStillActive.into());
+    }
+
     player_state.record_action(clock);
 
     if game.check_time_end_condition(clock.unix_timestamp) {
@@ -85,6 +96,12 @@ pub fn end_turn_handler(ctx: Context<EndTurn>) -> Result<()> {
     player_state.needs_chance_card = false;
     player_state.needs_community_chest_card = false;
     player_state.needs_bankruptcy_check = false;
+    player_state.needs_special_space_action = false;
+    player_state.pending_special_space_position = None;
+    player_state.double_rent_pending = false;
+
+    tick_festival_boost(game, player_state, clock);
+
     // Advance to next player
     player_state.doubles_count = 0; // Reset doubles count
                                     //let next_turn = (game.current_turn + 1) % game.current_players;
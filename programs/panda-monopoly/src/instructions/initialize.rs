@@ -1,20 +1,22 @@
 use crate::constants::*;
 use crate::error::GameError;
 use crate::state::*;
+use crate::utils::{transfer_to_sol_vault, validate_mint_extensions, SeedKey};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
     token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
-use ephemeral_rollups_sdk::anchor::delegate;
-use ephemeral_rollups_sdk::cpi::DelegateConfig;
+use ephemeral_rollups_sdk::anchor::{commit, delegate};
+use ephemeral_rollups_sdk::ephem::commit_and_undelegate_accounts;
 
 #[derive(Accounts)]
+#[instruction(entry_fee: u64, time_limit_seconds: Option<i64>, rules: Option<GameRules>, max_players: u8)]
 pub struct InitializeGame<'info> {
     #[account(
         init,
         payer = creator,
-        space = 8 + GameState::INIT_SPACE,
+        space = 8 + GameState::space_for(max_players),
         seeds = [
             b"game",
             config.id.as_ref(),
@@ -40,22 +42,43 @@ pub struct InitializeGame<'info> {
         mut,
         seeds = [b"platform", config.id.as_ref()],
         bump = config.bump,
+        constraint = !config.paused @ GameError::PlatformPaused,
     )]
     pub config: Box<Account<'info, PlatformConfig>>,
 
-    /// CHECK: game authority PDA
+    // Cards are copied into the game, later deck updates only apply to new games
+    #[account(
+        seeds = [CHANCE_DECK_SEED, config.id.as_ref()],
+        bump = chance_deck.bump,
+    )]
+    pub chance_deck: Box<Account<'info, CardDeck>>,
+
+    #[account(
+        seeds = [COMMUNITY_CHEST_DECK_SEED, config.id.as_ref()],
+        bump = community_chest_deck.bump,
+    )]
+    pub community_chest_deck: Box<Account<'info, CardDeck>>,
+
+    // Platform allowlist entry for the entry fee mint, required for paid games
+    #[account(
+        seeds = [ALLOWED_MINT_SEED, config.id.as_ref(), token_mint.seed_key().as_ref()],
+        bump = allowed_mint.bump,
+    )]
+    pub allowed_mint: Option<Box<Account<'info, AllowedMint>>>,
+
+    /// CHECK: game authority PDA - only required for token games
     #[account(
         seeds = [
-            GAME_AUTHORITY_SEED.as_ref(),
+            GAME_AUTHORITY_SEED,
         ],
         bump,
     )]
-    pub game_authority: UncheckedAccount<'info>,
+    pub game_authority: Option<UncheckedAccount<'info>>,
 
     #[account(
         mint::token_program = token_program,
     )]
-    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
 
     #[account(
         mut,
@@ -63,14 +86,13 @@ pub struct InitializeGame<'info> {
         associated_token::authority = creator,
         associated_token::token_program = token_program
     )]
-    // pub creator_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
-    pub creator_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub creator_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     #[account(
         init,
         seeds = [
-            TOKEN_VAULT_SEED.as_ref(),
-            token_mint.key().as_ref(),
+            TOKEN_VAULT_SEED,
+            token_mint.seed_key().as_ref(),
             game.key().as_ref(),
         ],
         token::mint = token_mint,
@@ -79,46 +101,113 @@ pub struct InitializeGame<'info> {
         payer = creator,
         bump
     )]
-    pub token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    // Lamport escrow, used instead of the token accounts when there is no mint
+    #[account(
+        mut,
+        seeds = [SOL_VAULT_SEED, game.key().as_ref()],
+        bump,
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
 
-    pub token_program: Interface<'info, TokenInterface>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
 
-    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
 
     pub system_program: Program<'info, System>,
 
     pub clock: Sysvar<'info, Clock>,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn initialize_game_handler(
     ctx: Context<InitializeGame>,
     entry_fee: u64,
     time_limit_seconds: Option<i64>,
+    rules: Option<GameRules>,
+    max_players: u8,
+    payout_bps: Option<Vec<u16>>,
+    delegation: Option<DelegationSettings>,
+    use_vrf: bool,
 ) -> Result<()> {
     let config = &mut ctx.accounts.config;
     let game = &mut ctx.accounts.game;
     let player_state = &mut ctx.accounts.player_state;
     let clock = &ctx.accounts.clock;
 
-    if entry_fee > 0 {
-        // require!(
-        //     ctx.accounts.game_authority.is_some()
-        //         && ctx.accounts.token_mint.is_some()
-        //         && ctx.accounts.creator_token_account.is_some()
-        //         && ctx.accounts.token_vault.is_some()
-        //         && ctx.accounts.token_program.is_some()
-        //         && ctx.accounts.associated_token_program.is_some(),
-        //     GameError::MissingTokenAccounts
-        // );
-
-        // let token_mint = ctx.accounts.token_mint.as_ref().unwrap();
-        let token_mint = &ctx.accounts.token_mint;
-        // let creator_token_account = ctx.accounts.creator_token_account.as_ref().unwrap();
-        let creator_token_account = &ctx.accounts.creator_token_account;
-        // let token_vault = ctx.accounts.token_vault.as_ref().unwrap();
-        let token_vault = &ctx.accounts.token_vault;
-        // let game_authority = ctx.accounts.game_authority.as_ref().unwrap();
-        let game_authority = &ctx.accounts.game_authority;
+    // House rules must fit the platform's allowed ranges
+    let rules = rules.unwrap_or_default();
+    config.rules_limits.validate_rules(&rules)?;
+
+    require!(
+        (MIN_PLAYERS..=MAX_PLAYERS).contains(&max_players),
+        GameError::InvalidMaxPlayers
+    );
+
+    // Winner takes all unless the creator picks a top-N split
+    let payout_bps = payout_bps.unwrap_or_else(|| vec![TOTAL_BASIS_POINTS]);
+    GameState::validate_payout_schedule(&payout_bps, max_players)?;
+
+    // Creators may tune the commit cadence, but only on the platform's validator
+    let delegation = delegation.unwrap_or(config.delegation);
+    delegation.validate()?;
+    require_keys_eq!(
+        delegation.validator,
+        config.delegation.validator,
+        GameError::UnapprovedValidator
+    );
+
+    // Paid games and sponsored freerolls must use a mint the platform allows,
+    // and paid games must stay within its stake limits
+    let fee_basis_points = if entry_fee > 0 || ctx.accounts.token_mint.is_some() {
+        let allowed_mint = ctx
+            .accounts
+            .allowed_mint
+            .as_ref()
+            .ok_or(GameError::MintNotAllowed)?;
+        if entry_fee > 0 {
+            allowed_mint.validate_entry_fee(entry_fee)?;
+        }
+        allowed_mint
+            .fee_basis_points_override
+            .unwrap_or(config.fee_basis_points)
+    } else {
+        config.fee_basis_points
+    };
+
+    if entry_fee == 0 && ctx.accounts.token_mint.is_none() {
+        // Free games skip the vault and its rent entirely
+        require!(
+            ctx.accounts.token_mint.is_none()
+                && ctx.accounts.creator_token_account.is_none()
+                && ctx.accounts.token_vault.is_none()
+                && ctx.accounts.sol_vault.is_none(),
+            GameError::UnexpectedTokenAccounts
+        );
+
+        game.token_mint = None;
+        game.token_vault = None;
+        game.total_prize_pool = 0;
+    } else if let Some(token_mint) = ctx.accounts.token_mint.as_ref() {
+        // A free game with a mint is a freeroll, its vault is funded by sponsors
+        validate_mint_extensions(&token_mint.to_account_info())?;
+
+        let token_vault = ctx
+            .accounts
+            .token_vault
+            .as_mut()
+            .ok_or(GameError::MissingTokenAccounts)?;
+        let token_program = ctx
+            .accounts
+            .token_program
+            .as_ref()
+            .ok_or(GameError::MissingTokenAccounts)?;
+        let game_authority = ctx
+            .accounts
+            .game_authority
+            .as_ref()
+            .ok_or(GameError::MissingTokenAccounts)?;
 
         // Validate game authority PDA
         let (expected_game_authority, _) =
@@ -129,15 +218,6 @@ pub fn initialize_game_handler(
             GameError::InvalidGameAuthority
         );
 
-        require!(
-            creator_token_account.mint == token_mint.key(),
-            GameError::InvalidTokenAccount
-        );
-        require!(
-            creator_token_account.owner == ctx.accounts.creator.key(),
-            GameError::InvalidTokenAccount
-        );
-
         require!(
             token_vault.mint == token_mint.key(),
             GameError::InvalidTokenAccount
@@ -147,32 +227,73 @@ pub fn initialize_game_handler(
             GameError::InvalidTokenAccount
         );
 
-        // Transfer entry fee from creator to vault
-        let transfer_accounts = TransferChecked {
-            from: creator_token_account.to_account_info(),
-            mint: token_mint.to_account_info(),
-            to: token_vault.to_account_info(),
-            authority: ctx.accounts.creator.to_account_info(),
-        };
-
-        let transfer_ctx = CpiContext::new(
-            ctx.accounts
-                .token_program
+        let received = if entry_fee > 0 {
+            let creator_token_account = ctx
+                .accounts
+                .creator_token_account
                 .as_ref()
-                // .unwrap()
-                .to_account_info(),
-            transfer_accounts,
-        );
+                .ok_or(GameError::MissingTokenAccounts)?;
+
+            require!(
+                creator_token_account.mint == token_mint.key(),
+                GameError::InvalidTokenAccount
+            );
+            require!(
+                creator_token_account.owner == ctx.accounts.creator.key(),
+                GameError::InvalidTokenAccount
+            );
+
+            // Transfer entry fee from creator to vault
+            let transfer_accounts = TransferChecked {
+                from: creator_token_account.to_account_info(),
+                mint: token_mint.to_account_info(),
+                to: token_vault.to_account_info(),
+                authority: ctx.accounts.creator.to_account_info(),
+            };
 
-        transfer_checked(transfer_ctx, entry_fee, token_mint.decimals)?;
+            let transfer_ctx = CpiContext::new(token_program.to_account_info(), transfer_accounts);
+
+            let vault_balance_before = token_vault.amount;
+            transfer_checked(transfer_ctx, entry_fee, token_mint.decimals)?;
+            token_vault.reload()?;
+
+            // Transfer-fee mints withhold part of the deposit, only credit what arrived
+            token_vault
+                .amount
+                .checked_sub(vault_balance_before)
+                .ok_or(GameError::ArithmeticUnderflow)?
+        } else {
+            0
+        };
 
         game.token_mint = Some(token_mint.key());
         game.token_vault = Some(token_vault.key());
-        game.total_prize_pool = entry_fee;
+        game.total_prize_pool = received;
     } else {
+        // No mint, escrow the entry fees as lamports
+        let sol_vault = ctx
+            .accounts
+            .sol_vault
+            .as_ref()
+            .ok_or(GameError::MissingSolVault)?;
+
+        // The escrow has no data, but must stay rent exempt between payouts
+        let rent_top_up = Rent::get()?
+            .minimum_balance(0)
+            .saturating_sub(sol_vault.lamports());
+
+        transfer_to_sol_vault(
+            &ctx.accounts.creator.to_account_info(),
+            &sol_vault.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            entry_fee
+                .checked_add(rent_top_up)
+                .ok_or(GameError::ArithmeticOverflow)?,
+        )?;
+
         game.token_mint = None;
         game.token_vault = None;
-        game.total_prize_pool = 0;
+        game.total_prize_pool = entry_fee;
     }
 
     let game_id = config.next_game_id;
@@ -187,13 +308,18 @@ pub fn initialize_game_handler(
     game.game_status = GameStatus::WaitingForPlayers;
     game.current_turn = 0;
     game.current_players = 0; // Initial player count
-    game.max_players = MAX_PLAYERS;
+    game.max_players = max_players;
     game.players = vec![];
     game.player_eliminated = vec![];
+    game.players_delegated = 0;
+    game.elimination_order = vec![];
+    game.final_ranking = vec![];
+    game.prize_claims = vec![false; payout_bps.len()];
+    game.payout_bps = payout_bps;
     game.total_players = 0;
     game.active_players = 0;
-    game.houses_remaining = TOTAL_HOUSES;
-    game.hotels_remaining = TOTAL_HOTELS;
+    game.houses_remaining = rules.total_houses;
+    game.hotels_remaining = rules.total_hotels;
     game.created_at = clock.unix_timestamp;
     game.bank_balance = 1_000_000; // Initial bank balance
     game.time_limit = time_limit_seconds;
@@ -203,15 +329,30 @@ pub fn initialize_game_handler(
     game.turn_started_at = clock.unix_timestamp;
     game.active_trades = vec![];
     game.next_trade_id = 0;
+    game.active_auction = None;
+    game.festival = None;
+    game.chance_deck = DeckState::new(ctx.accounts.chance_deck.cards.clone());
+    game.community_chest_deck = DeckState::new(ctx.accounts.community_chest_deck.cards.clone());
     game.entry_fee = entry_fee;
-    game.turn_timeout_seconds = DEFAULT_TURN_TIMEOUT_SECONDS;
-    game.turn_grace_period_seconds = DEFAULT_GRACE_PERIOD_SECONDS;
+    game.sponsor_pool = 0;
+    game.sponsor_count = 0;
+    game.fee_basis_points = fee_basis_points;
+    game.rules = rules;
+    game.delegation = delegation;
+    game.use_vrf = use_vrf;
+    game.randomness_slot = 0;
     game.timeout_enforcement_enabled = true;
 
     game.initialize_properties();
 
     // Initialize player state
-    player_state.initialize_player_state(ctx.accounts.creator.key(), game.key(), clock);
+    player_state.initialize_player_state(
+        ctx.accounts.creator.key(),
+        game.key(),
+        game.rules.starting_money,
+        clock,
+    );
+    player_state.entry_deposit = game.total_prize_pool;
 
     // Add player to game
     game.players.push(player_state.wallet);
@@ -235,7 +376,7 @@ pub struct JoinGame<'info> {
         seeds = [b"game", game.config_id.as_ref(), &game.game_id.to_le_bytes().as_ref()],
         bump = game.bump,
         constraint = game.game_status == GameStatus::WaitingForPlayers @ GameError::GameNotInProgress,
-        constraint = game.current_players < MAX_PLAYERS @ GameError::MaxPlayersReached
+        constraint = game.current_players < game.max_players @ GameError::MaxPlayersReached
     )]
     pub game: Box<Account<'info, GameState>>,
 
@@ -251,21 +392,26 @@ pub struct JoinGame<'info> {
     #[account(mut)]
     pub player: Signer<'info>,
 
-    // pub game_authority: Option<UncheckedAccount<'info>>,
-    /// CHECK: game authority PDA - only required for paid games
+    #[account(
+        seeds = [b"platform", game.config_id.as_ref()],
+        bump = config.bump,
+        constraint = !config.paused @ GameError::PlatformPaused,
+    )]
+    pub config: Box<Account<'info, PlatformConfig>>,
+
+    /// CHECK: game authority PDA - only required for token games
     #[account(
         seeds = [
-            GAME_AUTHORITY_SEED.as_ref(),
+            GAME_AUTHORITY_SEED,
         ],
         bump,
     )]
-    pub game_authority: UncheckedAccount<'info>,
+    pub game_authority: Option<UncheckedAccount<'info>>,
 
     #[account(
         mint::token_program = token_program,
     )]
-    // pub token_mint: Option<InterfaceAccount<'info, Mint>>,
-    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
 
     #[account(
         mut,
@@ -273,8 +419,7 @@ pub struct JoinGame<'info> {
         associated_token::authority = player,
         associated_token::token_program = token_program
     )]
-    // pub player_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
-    pub player_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub player_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     #[account(
         mut,
@@ -282,13 +427,18 @@ pub struct JoinGame<'info> {
         token::authority = game_authority,
         token::token_program = token_program,
     )]
-    // pub token_vault: Option<InterfaceAccount<'info, TokenAccount>>,
-    pub token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    // Lamport escrow, only required for games paid in SOL
+    #[account(
+        mut,
+        seeds = [SOL_VAULT_SEED, game.key().as_ref()],
+        bump,
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
 
-    // pub token_program: Option<Interface<'info, TokenInterface>>,
-    pub token_program: Interface<'info, TokenInterface>,
-    // pub associated_token_program: Option<Program<'info, AssociatedToken>>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
 
     pub system_program: Program<'info, System>,
     pub clock: Sysvar<'info, Clock>,
@@ -310,20 +460,59 @@ pub fn join_game_handler(ctx: Context<JoinGame>) -> Result<()> {
         }
     }
 
-    if game.entry_fee > 0 {
-        // require!(
-        //     ctx.accounts.game_authority.is_some()
-        //         && ctx.accounts.token_mint.is_some()
-        //         && ctx.accounts.player_token_account.is_some()
-        //         && ctx.accounts.token_vault.is_some()
-        //         && ctx.accounts.token_program.is_some(),
-        //     GameError::MissingTokenAccounts
-        // );
-
-        let token_mint = &ctx.accounts.token_mint;
-        let player_token_account = &ctx.accounts.player_token_account;
-        let token_vault = &ctx.accounts.token_vault;
-        let game_authority = &ctx.accounts.game_authority;
+    let prize_pool_before = game.total_prize_pool;
+
+    if game.uses_sol_vault() {
+        let sol_vault = ctx
+            .accounts
+            .sol_vault
+            .as_ref()
+            .ok_or(GameError::MissingSolVault)?;
+
+        transfer_to_sol_vault(
+            &ctx.accounts.player.to_account_info(),
+            &sol_vault.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            game.entry_fee,
+        )?;
+
+        game.total_prize_pool = game
+            .total_prize_pool
+            .checked_add(game.entry_fee)
+            .ok_or(GameError::ArithmeticOverflow)?;
+
+        msg!(
+            "Entry fee {} lamports paid by player {}",
+            game.entry_fee,
+            player_pubkey
+        );
+        msg!("Total prize pool: {}", game.total_prize_pool);
+    } else if game.entry_fee > 0 {
+        let token_mint = ctx
+            .accounts
+            .token_mint
+            .as_ref()
+            .ok_or(GameError::MissingTokenAccounts)?;
+        let player_token_account = ctx
+            .accounts
+            .player_token_account
+            .as_ref()
+            .ok_or(GameError::MissingTokenAccounts)?;
+        let token_vault = ctx
+            .accounts
+            .token_vault
+            .as_mut()
+            .ok_or(GameError::MissingTokenAccounts)?;
+        let token_program = ctx
+            .accounts
+            .token_program
+            .as_ref()
+            .ok_or(GameError::MissingTokenAccounts)?;
+        let game_authority = ctx
+            .accounts
+            .game_authority
+            .as_ref()
+            .ok_or(GameError::MissingTokenAccounts)?;
 
         let (expected_game_authority, _) =
             Pubkey::find_program_address(&[GAME_AUTHORITY_SEED], ctx.program_id);
@@ -367,20 +556,21 @@ pub fn join_game_handler(ctx: Context<JoinGame>) -> Result<()> {
             authority: ctx.accounts.player.to_account_info(),
         };
 
-        let transfer_ctx = CpiContext::new(
-            ctx.accounts
-                .token_program
-                .as_ref()
-                // .unwrap()
-                .to_account_info(),
-            transfer_accounts,
-        );
+        let transfer_ctx = CpiContext::new(token_program.to_account_info(), transfer_accounts);
 
+        let vault_balance_before = token_vault.amount;
         transfer_checked(transfer_ctx, game.entry_fee, token_mint.decimals)?;
+        token_vault.reload()?;
+
+        // Transfer-fee mints withhold part of the deposit, only credit what arrived
+        let received = token_vault
+            .amount
+            .checked_sub(vault_balance_before)
+            .ok_or(GameError::ArithmeticUnderflow)?;
 
         game.total_prize_pool = game
             .total_prize_pool
-            .checked_add(game.entry_fee)
+            .checked_add(received)
             .ok_or(GameError::ArithmeticOverflow)?;
 
         msg!(
@@ -389,21 +579,25 @@ pub fn join_game_handler(ctx: Context<JoinGame>) -> Result<()> {
             player_pubkey
         );
         msg!("Total prize pool: {}", game.total_prize_pool);
+    } else {
+        // For free games, ensure no token accounts are provided
+        require!(
+            ctx.accounts.token_mint.is_none()
+                && ctx.accounts.player_token_account.is_none()
+                && ctx.accounts.token_vault.is_none()
+                && ctx.accounts.sol_vault.is_none(),
+            GameError::UnexpectedTokenAccounts
+        );
     }
-    // else {
-    //     // For free games, ensure no token accounts are provided
-    //     require!(
-    //         ctx.accounts.game_authority.is_none()
-    //             && ctx.accounts.token_mint.is_none()
-    //             && ctx.accounts.player_token_account.is_none()
-    //             && ctx.accounts.token_vault.is_none()
-    //             && ctx.accounts.token_program.is_none(),
-    //         GameError::UnexpectedTokenAccounts
-    //     );
-    // }
 
     // Initialize player state
-    player_state.initialize_player_state(player_pubkey, game.key(), clock);
+    player_state.initialize_player_state(
+        player_pubkey,
+        game.key(),
+        game.rules.starting_money,
+        clock,
+    );
+    player_state.entry_deposit = game.total_prize_pool - prize_pool_before;
 
     // Add player to game
     game.players.push(player_pubkey);
@@ -441,7 +635,7 @@ pub struct StartGame<'info> {
         mut,
         seeds = [b"game", game.config_id.as_ref(), &game.game_id.to_le_bytes().as_ref()],
         bump,
-        constraint = game.game_status == GameStatus::WaitingForPlayers @ GameError::GameNotInProgress,
+        constraint = matches!(game.game_status, GameStatus::WaitingForPlayers | GameStatus::Starting) @ GameError::GameNotInProgress,
         constraint = game.current_players >= MIN_PLAYERS @ GameError::MinPlayersNotMet,
         constraint = authority.key() == game.creator @ GameError::Unauthorized,
         del
@@ -451,21 +645,128 @@ pub struct StartGame<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
 
+    // A start already in progress may finish so no game is left half delegated
+    #[account(
+        seeds = [b"platform", game.config_id.as_ref()],
+        bump = config.bump,
+        constraint = !config.paused || game.game_status == GameStatus::Starting @ GameError::PlatformPaused,
+    )]
+    pub config: Box<Account<'info, PlatformConfig>>,
+
     pub clock: Sysvar<'info, Clock>,
 }
 
+/// Delegates up to `MAX_PLAYERS_PER_BATCH` player accounts per call. Larger
+/// games call this repeatedly; the game itself is delegated with the last batch.
 pub fn start_game_handler<'c: 'info, 'info>(
     ctx: Context<'_, '_, 'c, 'info, StartGame<'info>>,
 ) -> Result<()> {
+    let mut delegated_count: u8 = 0;
+
+    // A stalled start may be rolled back at any moment, so it can't resume
+    require!(
+        !ctx.accounts
+            .game
+            .is_start_stalled(ctx.accounts.clock.unix_timestamp),
+        GameError::StartStalled
+    );
+
+    {
+        msg!("Start delegate");
+
+        let authority = &ctx.accounts.authority;
+        let owner_program = &ctx.accounts.owner_program;
+        let delegation_program = &ctx.accounts.delegation_program;
+        let game = &ctx.accounts.game;
+        let game_key = game.key();
+        let pending_players = &game.players[game.players_delegated as usize..];
+
+        // remaining_accounts: [player_state, buffer, delegation_record, delegation_metadata] per player
+        for (player_pubkey, player_accounts) in pending_players
+            .iter()
+            .zip(ctx.remaining_accounts.chunks_exact(4))
+            .take(MAX_PLAYERS_PER_BATCH)
+        {
+            let player_account = &player_accounts[0];
+
+            let expected_player_state_key = Pubkey::find_program_address(
+                &[b"player", game_key.as_ref(), player_pubkey.as_ref()],
+                ctx.program_id,
+            )
+            .0;
+
+            require!(
+                player_account.key() == expected_player_state_key,
+                GameError::InvalidPlayerAccount
+            );
+
+            // Commit-reveal games can't roll without a committed hash chain
+            if !game.use_vrf {
+                let player_state = Account::<PlayerState>::try_from(player_account)?;
+                require!(
+                    player_state.randomness_commitment.is_some(),
+                    GameError::MissingRandomnessCommitment
+                );
+            }
+
+            player_account.exit(&crate::ID)?;
+
+            let del_accounts = ephemeral_rollups_sdk::cpi::DelegateAccounts {
+                payer: &authority.to_account_info(),
+                pda: &player_account.to_account_info(),
+                owner_program: &owner_program.to_account_info(),
+                buffer: &player_accounts[1],
+                delegation_record: &player_accounts[2],
+                delegation_metadata: &player_accounts[3],
+                delegation_program: &delegation_program.to_account_info(),
+                system_program: &ctx.accounts.system_program.to_account_info(),
+            };
+
+            let seeds = &[b"player", game_key.as_ref(), player_pubkey.as_ref()];
+
+            ephemeral_rollups_sdk::cpi::delegate_account(
+                del_accounts,
+                seeds,
+                game.delegation.delegate_config(),
+            )?;
+
+            delegated_count += 1;
+
+            msg!("Player {} delegated", player_pubkey);
+        }
+
+        require!(
+            delegated_count > 0 || pending_players.is_empty(),
+            GameError::MissingPlayerAccount
+        );
+    }
+
     {
         let game = &mut ctx.accounts.game;
         let clock = &ctx.accounts.clock;
 
+        game.players_delegated += delegated_count;
+
+        if (game.players_delegated as usize) < game.players.len() {
+            // Lock the lobby until every player account is delegated
+            game.game_status = GameStatus::Starting;
+            game.turn_started_at = clock.unix_timestamp;
+
+            msg!(
+                "Delegated {}/{} players, call start_game again to continue",
+                game.players_delegated,
+                game.players.len()
+            );
+
+            return Ok(());
+        }
+
         // Change game status to in progress
         game.game_status = GameStatus::InProgress;
         game.current_turn = 0; // First player starts
         game.turn_started_at = clock.unix_timestamp;
         game.started_at = Some(clock.unix_timestamp);
+        game.randomness_slot = clock.slot;
 
         if let Some(limit) = game.time_limit {
             game.game_end_time = Some(clock.unix_timestamp + limit);
@@ -475,14 +776,7 @@ pub fn start_game_handler<'c: 'info, 'info>(
     }
 
     {
-        msg!("Start delegate");
-
-        let authority = &ctx.accounts.authority;
-        let owner_program = &ctx.accounts.owner_program;
-        let delegation_program = &ctx.accounts.delegation_program;
         let game = &ctx.accounts.game;
-        let game_key = game.key();
-        let players = &game.players;
 
         game.exit(&crate::ID)?;
         ctx.accounts.delegate_game(
@@ -492,67 +786,96 @@ pub fn start_game_handler<'c: 'info, 'info>(
                 game.config_id.as_ref(),
                 &game.game_id.to_le_bytes(),
             ],
-            DelegateConfig {
-                commit_frequency_ms: 30_000,
-                validator: Some(pubkey!("MAS1Dt9qreoRMQ14YQuhg8UTZMMzDdKhmkZMECCzk57")),
-            },
+            game.delegation.delegate_config(),
         )?;
+    }
 
-        let remaining_accounts_iter = &mut ctx.remaining_accounts.iter();
+    msg!("Game started!");
 
-        // delegate player accounts
-        for player_pubkey in players.iter() {
-            let player_account = remaining_accounts_iter
-                .next()
-                .ok_or(GameError::InvalidAccount)?;
-            player_account.exit(&crate::ID)?;
+    let game = &ctx.accounts.game;
+    let clock = &ctx.accounts.clock;
+    emit!(GameStarted {
+        game: game.key(),
+        total_players: game.current_players,
+        first_player: game.players[0],
+        timestamp: clock.unix_timestamp,
+    });
 
-            let player_buffer_account = remaining_accounts_iter
-                .next()
-                .ok_or(GameError::InvalidAccount)?;
+    Ok(())
+}
 
-            let player_delegation_record_account = remaining_accounts_iter
-                .next()
-                .ok_or(GameError::InvalidAccount)?;
+#[commit]
+#[derive(Accounts)]
+pub struct UndelegateStalledStart<'info> {
+    #[account(
+        seeds = [b"game", game.config_id.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump,
+        constraint = game.game_status == GameStatus::Starting @ GameError::GameNotStarting,
+    )]
+    pub game: Box<Account<'info, GameState>>,
 
-            let player_delegation_metadata_account = remaining_accounts_iter
-                .next()
-                .ok_or(GameError::InvalidAccount)?;
+    #[account(mut)]
+    pub payer: Signer<'info>,
 
-            let del_accounts = ephemeral_rollups_sdk::cpi::DelegateAccounts {
-                payer: &authority.to_account_info(),
-                pda: &player_account.to_account_info(),
-                owner_program: &owner_program.to_account_info(),
-                buffer: player_buffer_account,
-                delegation_record: player_delegation_record_account,
-                delegation_metadata: player_delegation_metadata_account,
-                delegation_program: &delegation_program.to_account_info(),
-                system_program: &ctx.accounts.system_program.to_account_info(),
-            };
+    pub clock: Sysvar<'info, Clock>,
+    // remaining_accounts: PlayerState of every delegated player, in seating order
+}
 
-            let seeds = &[b"player", game_key.as_ref(), player_pubkey.as_ref()];
+/// Hands the delegated player accounts of a stalled start back to the base
+/// layer so the game can be cancelled and refunded. Runs on the ER; any
+/// player can call it once the start has stalled.
+pub fn undelegate_stalled_start_handler<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, UndelegateStalledStart<'info>>,
+) -> Result<()> {
+    let game = &ctx.accounts.game;
+    let game_key = game.key();
+    let payer = &ctx.accounts.payer;
 
-            let config = DelegateConfig {
-                commit_frequency_ms: 30_000,
-                validator: Some(pubkey!("MAS1Dt9qreoRMQ14YQuhg8UTZMMzDdKhmkZMECCzk57")),
-            };
+    require!(
+        game.players.contains(&payer.key()),
+        GameError::PlayerNotFound
+    );
+    require!(
+        game.is_start_stalled(ctx.accounts.clock.unix_timestamp),
+        GameError::StartNotStalled
+    );
 
-            ephemeral_rollups_sdk::cpi::delegate_account(del_accounts, seeds, config)?;
+    let delegated_players = &game.players[..game.players_delegated as usize];
 
-            msg!("Player {} delegated", player_pubkey);
-        }
+    require!(
+        ctx.remaining_accounts.len() >= delegated_players.len(),
+        GameError::MissingPlayerAccount
+    );
+
+    let mut accounts: Vec<&AccountInfo<'info>> = Vec::with_capacity(delegated_players.len());
+
+    for (player_pubkey, player_account) in delegated_players.iter().zip(ctx.remaining_accounts) {
+        let expected_player_state_key = Pubkey::find_program_address(
+            &[b"player", game_key.as_ref(), player_pubkey.as_ref()],
+            ctx.program_id,
+        )
+        .0;
+
+        require!(
+            player_account.key() == expected_player_state_key,
+            GameError::InvalidPlayerAccount
+        );
+
+        accounts.push(player_account);
     }
 
-    msg!("Game started!");
+    commit_and_undelegate_accounts(
+        payer,
+        accounts,
+        &ctx.accounts.magic_context,
+        &ctx.accounts.magic_program,
+    )?;
 
-    let game = &ctx.accounts.game;
-    let clock = &ctx.accounts.clock;
-    emit!(GameStarted {
-        game: game.key(),
-        total_players: game.current_players,
-        first_player: game.players[0],
-        timestamp: clock.unix_timestamp,
-    });
+    msg!(
+        "Stalled start of game {} rolled back, {} players undelegated",
+        game.game_id,
+        delegated_players.len()
+    );
 
     Ok(())
 }
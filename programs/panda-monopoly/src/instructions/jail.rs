@@ -1,5 +1,5 @@
 use crate::error::GameError;
-use crate::{constants::*, force_end_turn_util, state::*};
+use crate::{force_end_turn_util, state::*};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -15,7 +15,8 @@ pub struct PayJailFine<'info> {
     #[account(
         mut,
         seeds = [b"player", game.key().as_ref(), player.key().as_ref()],
-        bump
+        bump,
+        constraint = !player_state.needs_bankruptcy_check @ GameError::DebtOutstanding
     )]
     pub player_state: Box<Account<'info, PlayerState>>,
 
@@ -46,14 +47,16 @@ pub fn pay_jail_fine_handler(ctx: Context<PayJailFine>) -> Result<()> {
         return Err(GameError::PlayerNotInJail.into());
     }
 
-    // Check if player has enough money
-    if player_state.cash_balance < JAIL_FINE as u64 {
-        player_state.needs_bankruptcy_check = true;
-        return Ok(());
-    }
+    // Fine is optional, the player can still roll for doubles
+    require!(
+        player_state.cash_balance >= game.rules.jail_fine,
+        GameError::InsufficientFunds
+    );
 
     // Pay fine and release from jail
-    player_state.cash_balance -= JAIL_FINE as u64;
+    let jail_fine = game.rules.jail_fine;
+    player_state.cash_balance -= jail_fine;
+    game.add_to_free_parking(jail_fine);
     player_state.in_jail = false;
     player_state.jail_turns = 0;
 
@@ -63,7 +66,7 @@ pub fn pay_jail_fine_handler(ctx: Context<PayJailFine>) -> Result<()> {
     msg!(
         "Player {} paid ${} jail fine and is released! Turn ended.",
         player_pubkey,
-        JAIL_FINE
+        game.rules.jail_fine
     );
 
     Ok(())
@@ -82,7 +85,8 @@ pub struct UseGetOutOfJailCard<'info> {
     #[account(
         mut,
         seeds = [b"player", game.key().as_ref(), player.key().as_ref()],
-        bump
+        bump,
+        constraint = !player_state.needs_bankruptcy_check @ GameError::DebtOutstanding
     )]
     pub player_state: Box<Account<'info, PlayerState>>,
 
@@ -114,12 +118,12 @@ pub fn use_get_out_of_jail_card_handler(ctx: Context<UseGetOutOfJailCard>) -> Re
     }
 
     // Check if player has get out of jail cards
-    if player_state.get_out_of_jail_cards == 0 {
+    let Some(card) = player_state.get_out_of_jail_cards.pop() else {
         return Err(GameError::NoGetOutOfJailCards.into());
-    }
+    };
 
     // Use the card and release from jail
-    player_state.get_out_of_jail_cards -= 1;
+    game.return_jail_card(card);
     player_state.in_jail = false;
     player_state.jail_turns = 0;
 
@@ -129,7 +133,7 @@ pub fn use_get_out_of_jail_card_handler(ctx: Context<UseGetOutOfJailCard>) -> Re
     msg!(
         "Player {} used a Get Out of Jail card and is released! Turn ended. Cards remaining: {}",
         player_pubkey,
-        player_state.get_out_of_jail_cards
+        player_state.jail_card_count()
     );
 
     Ok(())
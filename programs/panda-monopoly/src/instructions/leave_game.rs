@@ -1,6 +1,7 @@
 // instructions/leave_game.rs
 use crate::error::GameError;
 use crate::state::*;
+use crate::utils::transfer_from_sol_vault;
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{
     transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
@@ -27,17 +28,17 @@ pub struct LeaveGame<'info> {
     #[account(mut)]
     pub player: Signer<'info>,
 
-    /// CHECK: game authority PDA - validated in handler
+    /// CHECK: game authority PDA - only required for token games
     #[account(
-        seeds = [crate::constants::GAME_AUTHORITY_SEED.as_ref()],
+        seeds = [crate::constants::GAME_AUTHORITY_SEED],
         bump,
     )]
-    pub game_authority: UncheckedAccount<'info>,
+    pub game_authority: Option<UncheckedAccount<'info>>,
 
     #[account(
         mint::token_program = token_program,
     )]
-    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
 
     #[account(
         mut,
@@ -45,7 +46,7 @@ pub struct LeaveGame<'info> {
         associated_token::authority = player,
         associated_token::token_program = token_program
     )]
-    pub player_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub player_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     #[account(
         mut,
@@ -53,9 +54,17 @@ pub struct LeaveGame<'info> {
         token::authority = game_authority,
         token::token_program = token_program,
     )]
-    pub token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
-    pub token_program: Interface<'info, TokenInterface>,
+    // Lamport escrow, only required for games paid in SOL
+    #[account(
+        mut,
+        seeds = [crate::constants::SOL_VAULT_SEED, game.key().as_ref()],
+        bump,
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
 
     pub system_program: Program<'info, System>,
     pub clock: Sysvar<'info, Clock>,
@@ -77,11 +86,60 @@ pub fn leave_game_handler(ctx: Context<LeaveGame>) -> Result<()> {
         .position(|&p| p == player_pubkey)
         .ok_or(GameError::PlayerNotFound)?;
 
-    if game.entry_fee > 0 {
-        let token_mint = &ctx.accounts.token_mint;
-        let player_token_account = &ctx.accounts.player_token_account;
-        let token_vault = &ctx.accounts.token_vault;
-        let game_authority = &ctx.accounts.game_authority;
+    // What the player's entry actually added to the pool
+    let refund_amount = ctx.accounts.player_state.entry_deposit;
+
+    if game.uses_sol_vault() {
+        let sol_vault = ctx
+            .accounts
+            .sol_vault
+            .as_ref()
+            .ok_or(GameError::MissingSolVault)?;
+
+        transfer_from_sol_vault(
+            &sol_vault.to_account_info(),
+            &ctx.accounts.player.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &game.key(),
+            refund_amount,
+        )?;
+
+        game.total_prize_pool = game
+            .total_prize_pool
+            .checked_sub(refund_amount)
+            .ok_or(GameError::ArithmeticUnderflow)?;
+
+        msg!(
+            "Entry fee {} lamports refunded to player {}",
+            refund_amount,
+            player_pubkey
+        );
+    } else if game.entry_fee > 0 {
+        let token_mint = ctx
+            .accounts
+            .token_mint
+            .as_ref()
+            .ok_or(GameError::MissingTokenAccounts)?;
+        let player_token_account = ctx
+            .accounts
+            .player_token_account
+            .as_ref()
+            .ok_or(GameError::MissingTokenAccounts)?;
+        let token_vault = ctx
+            .accounts
+            .token_vault
+            .as_ref()
+            .ok_or(GameError::MissingTokenAccounts)?;
+        let token_program = ctx
+            .accounts
+            .token_program
+            .as_ref()
+            .ok_or(GameError::MissingTokenAccounts)?;
+        let game_authority = ctx
+            .accounts
+            .game_authority
+            .as_ref()
+            .ok_or(GameError::MissingTokenAccounts)?;
 
         let (expected_game_authority, game_authority_bump) =
             Pubkey::find_program_address(&[crate::constants::GAME_AUTHORITY_SEED], ctx.program_id);
@@ -114,23 +172,31 @@ pub fn leave_game_handler(ctx: Context<LeaveGame>) -> Result<()> {
         };
 
         let transfer_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
+            token_program.to_account_info(),
             transfer_accounts,
             signer_seeds,
         );
 
-        transfer_checked(transfer_ctx, game.entry_fee, token_mint.decimals)?;
+        transfer_checked(transfer_ctx, refund_amount, token_mint.decimals)?;
 
         game.total_prize_pool = game
             .total_prize_pool
-            .checked_sub(game.entry_fee)
+            .checked_sub(refund_amount)
             .ok_or(GameError::ArithmeticUnderflow)?;
 
         msg!(
             "Entry fee {} refunded to player {}",
-            game.entry_fee,
+            refund_amount,
             player_pubkey
         );
+    } else {
+        require!(
+            ctx.accounts.token_mint.is_none()
+                && ctx.accounts.player_token_account.is_none()
+                && ctx.accounts.token_vault.is_none()
+                && ctx.accounts.sol_vault.is_none(),
+            GameError::UnexpectedTokenAccounts
+        );
     }
 
     game.players.remove(player_index);
@@ -157,7 +223,7 @@ pub fn leave_game_handler(ctx: Context<LeaveGame>) -> Result<()> {
     emit!(PlayerLeft {
         game: game.key(),
         player: player_pubkey,
-        refund_amount: game.entry_fee,
+        refund_amount,
         remaining_players: game.current_players,
         timestamp: clock.unix_timestamp,
     });
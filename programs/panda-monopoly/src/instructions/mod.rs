@@ -1,4 +1,4 @@
-// pub mod auction;
+pub mod auction;
 pub mod bankruptcy;
 pub mod dice;
 pub mod end_game;
@@ -8,14 +8,17 @@ pub mod jail;
 pub mod platform;
 pub mod property;
 pub mod special_spaces;
+#[cfg(feature = "local")]
 pub mod test;
 pub mod trading;
 pub mod claim_reward;
 pub mod leave_game;
 pub mod cancel_game;
+pub mod close_game;
 pub mod permissionless;
+pub mod sponsor;
 
-// pub use auction::*;
+pub use auction::*;
 pub use bankruptcy::*;
 pub use dice::*;
 pub use end_game::*;
@@ -25,9 +28,12 @@ pub use jail::*;
 pub use platform::*;
 pub use property::*;
 pub use special_spaces::*;
+#[cfg(feature = "local")]
 pub use test::*;
 pub use trading::*;
 pub use claim_reward::*;
 pub use leave_game::*;
 pub use cancel_game::*;
+pub use close_game::*;
 pub use permissionless::*;
+pub use sponsor::*;
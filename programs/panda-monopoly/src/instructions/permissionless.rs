@@ -1,5 +1,6 @@
 use crate::constants::*;
 use crate::error::GameError;
+use crate::instructions::{resolve_creditor, transfer_estate_to_bank, transfer_estate_to_creditor};
 use crate::state::*;
 use anchor_lang::prelude::*;
 
@@ -51,26 +52,29 @@ pub fn force_end_turn_handler(ctx: Context<ForceEndTurn>) -> Result<()> {
         GameError::NotPlayerTurn
     );
 
+    // Turn is paused while an auction is open; settle_auction resumes it
+    require!(!game.has_active_auction(), GameError::AuctionStillActive);
+
     // 2. Calculate time elapsed since turn started
     let current_time = clock.unix_timestamp;
     let time_elapsed = current_time.saturating_sub(game.turn_started_at);
 
     // 3. Check grace period first
     require!(
-        time_elapsed >= game.turn_grace_period_seconds as i64,
+        time_elapsed >= game.rules.turn_grace_period_seconds as i64,
         GameError::GracePeriodNotExpired
     );
 
     // 4. Check if timeout has been reached
     require!(
-        time_elapsed >= game.turn_timeout_seconds as i64,
+        time_elapsed >= game.rules.turn_timeout_seconds as i64,
         GameError::TimeoutNotReached
     );
 
     // 5. Verify player hasn't taken recent action
     let action_elapsed = current_time.saturating_sub(player_state.last_action_timestamp);
     require!(
-        action_elapsed >= game.turn_grace_period_seconds as i64,
+        action_elapsed >= game.rules.turn_grace_period_seconds as i64,
         GameError::PlayerHasRecentActivity
     );
 
@@ -113,6 +117,7 @@ pub fn force_end_turn_handler(ctx: Context<ForceEndTurn>) -> Result<()> {
     let next_turn = find_next_active_player(game, game.current_turn)?;
     game.current_turn = next_turn;
     game.turn_started_at = clock.unix_timestamp;
+    game.randomness_slot = clock.slot;
 
     msg!(
         "Turn force-ended. Next player: {} (index {})",
@@ -160,6 +165,14 @@ pub struct ForceBankruptcyForTimeout<'info> {
     )]
     pub timed_out_player_state: Box<Account<'info, PlayerState>>,
 
+    // Required when the player owes an unpaid debt to another player
+    #[account(
+        mut,
+        seeds = [b"player", game.key().as_ref(), creditor_state.wallet.as_ref()],
+        bump
+    )]
+    pub creditor_state: Option<Box<Account<'info, PlayerState>>>,
+
     /// CHECK: The player being bankrupted
     #[account(mut)]
     pub timed_out_player: UncheckedAccount<'info>,
@@ -195,8 +208,9 @@ pub fn force_bankruptcy_for_timeout_handler(ctx: Context<ForceBankruptcyForTimeo
         .position(|&p| p == timed_out_player_pubkey)
         .ok_or(GameError::PlayerNotFound)?;
 
-    // 3. Execute bankruptcy logic
-    execute_bankruptcy(game, player_state, timed_out_player_pubkey)?;
+    // 3. Execute bankruptcy logic, paying any creditor still in the game
+    let creditor_state = resolve_creditor(player_state, ctx.accounts.creditor_state.as_mut())?;
+    execute_bankruptcy(game, player_state, creditor_state, timed_out_player_pubkey)?;
 
     // 4. Remove player from game
     remove_player_from_game(game, player_index as u8)?;
@@ -204,13 +218,12 @@ pub fn force_bankruptcy_for_timeout_handler(ctx: Context<ForceBankruptcyForTimeo
     // 5. Check if game should end
     if check_game_end_condition(game) {
         game.game_status = GameStatus::Finished;
+        game.ended_at = Some(clock.unix_timestamp);
+
+        let survivors = game.get_active_players();
+        game.record_final_ranking(&survivors);
 
-        if let Some(winner_pubkey) = game
-            .players
-            .iter()
-            .find(|&&p| p != Pubkey::default())
-            .copied()
-        {
+        if let Some(&winner_pubkey) = survivors.first() {
             game.winner = Some(winner_pubkey);
             msg!("Game ended. Winner: {}", winner_pubkey);
 
@@ -220,6 +233,7 @@ pub fn force_bankruptcy_for_timeout_handler(ctx: Context<ForceBankruptcyForTimeo
                 ended_at: clock.unix_timestamp,
                 reason: GameEndReason::BankruptcyVictory,
                 winner_net_worth: None,
+                ranking: game.final_ranking.clone(),
             });
         }
     } else {
@@ -258,7 +272,7 @@ fn find_next_active_player(game: &GameState, current_turn: u8) -> Result<u8> {
 
     // Search for next active player
     while attempts < total_players {
-        if game.players[next_turn as usize] != Pubkey::default() {
+        if !game.player_eliminated[next_turn as usize] {
             return Ok(next_turn);
         }
         next_turn = (next_turn + 1) % total_players;
@@ -272,119 +286,51 @@ fn find_next_active_player(game: &GameState, current_turn: u8) -> Result<u8> {
 fn execute_bankruptcy(
     game: &mut GameState,
     player_state: &mut PlayerState,
+    creditor_state: Option<&mut PlayerState>,
     player_pubkey: Pubkey,
 ) -> Result<()> {
     // Mark player as bankrupt
     player_state.is_bankrupt = true;
 
-    // Calculate total liquidation value
-    let mut total_liquidation_value = 0u64;
-    let mut houses_returned = 0u8;
-    let mut hotels_returned = 0u8;
-
-    // Process all properties
-    for &position in &player_state.properties_owned.clone() {
-        let property = game.get_property_mut(position)?;
-
-        if property.owner == Some(player_pubkey) {
-            let property_data = crate::constants::get_property_data(position)?;
-
-            // Calculate building value
-            let building_value = calculate_building_liquidation_value(property, property_data)?;
-            total_liquidation_value = total_liquidation_value
-                .checked_add(building_value)
-                .ok_or(GameError::ArithmeticOverflow)?;
-
-            // Return buildings
-            if property.houses > 0 {
-                houses_returned = houses_returned
-                    .checked_add(property.houses)
-                    .ok_or(GameError::ArithmeticOverflow)?;
-                property.houses = 0;
-            }
-
-            if property.has_hotel {
-                hotels_returned = hotels_returned
-                    .checked_add(1)
-                    .ok_or(GameError::ArithmeticOverflow)?;
-                property.has_hotel = false;
-            }
-
-            // Add mortgage value
-            if !property.is_mortgaged {
-                total_liquidation_value = total_liquidation_value
-                    .checked_add(property_data.mortgage_value)
-                    .ok_or(GameError::ArithmeticOverflow)?;
-            }
-
-            // Clear ownership
-            property.owner = None;
-            property.is_mortgaged = false;
+    match creditor_state {
+        Some(creditor_state) => {
+            let creditor = creditor_state.wallet;
+            let (building_value, remaining_cash) =
+                transfer_estate_to_creditor(game, player_state, creditor_state)?;
+
+            msg!(
+                "Player {} bankrupted. Estate handed to creditor {}: ${} from buildings, ${} cash",
+                player_pubkey,
+                creditor,
+                building_value,
+                remaining_cash
+            );
+        }
+        None => {
+            let (total_liquidation_value, remaining_cash, houses_returned, hotels_returned) =
+                transfer_estate_to_bank(game, player_state)?;
+
+            msg!(
+                "Player {} bankrupted. Liquidated ${}, ${} cash, {} houses, {} hotels",
+                player_pubkey,
+                total_liquidation_value,
+                remaining_cash,
+                houses_returned,
+                hotels_returned
+            );
         }
     }
 
-    // Return buildings to bank
-    game.houses_remaining = game
-        .houses_remaining
-        .checked_add(houses_returned)
-        .ok_or(GameError::ArithmeticOverflow)?;
-
-    game.hotels_remaining = game
-        .hotels_remaining
-        .checked_add(hotels_returned)
-        .ok_or(GameError::ArithmeticOverflow)?;
-
-    // Add to bank balance
-    let remaining_cash = player_state.cash_balance;
-    game.bank_balance = game
-        .bank_balance
-        .checked_add(total_liquidation_value)
-        .unwrap()
-        .checked_add(remaining_cash)
-        .ok_or(GameError::ArithmeticOverflow)?;
-
     // Clear player state
     player_state.cash_balance = 0;
     player_state.net_worth = 0;
     player_state.properties_owned.clear();
-    player_state.get_out_of_jail_cards = 0;
+    player_state.get_out_of_jail_cards.clear();
     reset_player_state_for_bankruptcy(player_state);
 
-    msg!(
-        "Player {} bankrupted. Liquidated ${}, ${} cash, {} houses, {} hotels",
-        player_pubkey,
-        total_liquidation_value,
-        remaining_cash,
-        houses_returned,
-        hotels_returned
-    );
-
     Ok(())
 }
 
-fn calculate_building_liquidation_value(
-    property: &PropertyInfo,
-    property_data: &crate::constants::PropertyData,
-) -> Result<u64> {
-    let mut value = 0u64;
-
-    if property.houses > 0 {
-        let house_value = (property_data.house_cost / 2) * property.houses as u64;
-        value = value
-            .checked_add(house_value)
-            .ok_or(GameError::ArithmeticOverflow)?;
-    }
-
-    if property.has_hotel {
-        let hotel_value = (property_data.house_cost * 5) / 2;
-        value = value
-            .checked_add(hotel_value)
-            .ok_or(GameError::ArithmeticOverflow)?;
-    }
-
-    Ok(value)
-}
-
 fn reset_player_state_for_bankruptcy(player_state: &mut PlayerState) {
     player_state.has_rolled_dice = false;
     player_state.needs_property_action = false;
@@ -399,12 +345,17 @@ fn reset_player_state_for_bankruptcy(player_state: &mut PlayerState) {
     player_state.position = 0;
     player_state.festival_boost_turns = 0;
     player_state.card_drawn_at = None;
-    player_state.needs_bankruptcy_check = false;
+    player_state.double_rent_pending = false;
+    player_state.clear_debt();
 }
 
 fn remove_player_from_game(game: &mut GameState, player_index: u8) -> Result<()> {
+    // Keep the key so the player's account can still be found when the game ends
     if (player_index as usize) < game.players.len() {
-        game.players[player_index as usize] = Pubkey::default();
+        game.player_eliminated[player_index as usize] = true;
+
+        let player = game.players[player_index as usize];
+        game.elimination_order.push(player);
     }
 
     game.current_players = game
@@ -417,9 +368,9 @@ fn remove_player_from_game(game: &mut GameState, player_index: u8) -> Result<()>
 
 fn check_game_end_condition(game: &GameState) -> bool {
     let active_count = game
-        .players
+        .player_eliminated
         .iter()
-        .filter(|&&p| p != Pubkey::default())
+        .filter(|&&eliminated| !eliminated)
         .count();
     active_count <= 1
 }
@@ -1,6 +1,12 @@
 use anchor_lang::prelude::*;
 
-use crate::PlatformConfig;
+use crate::{
+    error::GameError, AllowedMint, AllowedMintAdded, AllowedMintRemoved, CardData, CardDeck,
+    CardDeckUpdated, CardEffectType, DeckKind, DelegationSettings, GameRulesLimits,
+    PlatformAuthorityProposed, PlatformAuthorityTransferred, PlatformConfig, PlatformPauseUpdated,
+    ALLOWED_MINT_SEED, BOARD_SIZE, DEFAULT_CHANCE_CARDS, DEFAULT_COMMUNITY_CHEST_CARDS,
+    MAX_CARDS_PER_DECK, MAX_FEE_BASIS_POINTS, MAX_JAIL_CARDS_PER_DECK,
+};
 
 #[derive(Accounts)]
 #[instruction(platform_id: Pubkey)]
@@ -9,7 +15,7 @@ pub struct CreatePlatformConfig<'info> {
     pub admin: Signer<'info>,
 
     #[account(
-        init_if_needed,
+        init,
         seeds = [b"platform", platform_id.as_ref()],
         bump,
         payer = admin,
@@ -28,6 +34,11 @@ pub fn create_platform_config_handler(
 ) -> Result<()> {
     let config = &mut ctx.accounts.config;
 
+    require!(
+        fee_basis_points <= MAX_FEE_BASIS_POINTS,
+        GameError::InvalidFeeBasisPoints
+    );
+
     config.id = platform_id;
     config.fee_basis_points = fee_basis_points;
     config.fee_vault = fee_vault;
@@ -35,6 +46,10 @@ pub fn create_platform_config_handler(
     config.authority = ctx.accounts.admin.key();
     config.total_games_created = 0;
     config.next_game_id = 1;
+    config.rules_limits = GameRulesLimits::default();
+    config.pending_authority = None;
+    config.paused = false;
+    config.delegation = DelegationSettings::default();
 
     Ok(())
 }
@@ -56,10 +71,16 @@ pub fn update_platform_config_handler(
     ctx: Context<UpdatePlatformConfig>,
     fee_basis_points: Option<u16>,
     fee_vault: Option<Pubkey>,
+    rules_limits: Option<GameRulesLimits>,
+    delegation: Option<DelegationSettings>,
 ) -> Result<()> {
     let config = &mut ctx.accounts.config;
 
     if let Some(fee) = fee_basis_points {
+        require!(
+            fee <= MAX_FEE_BASIS_POINTS,
+            GameError::InvalidFeeBasisPoints
+        );
         config.fee_basis_points = fee;
     }
 
@@ -67,5 +88,306 @@ pub fn update_platform_config_handler(
         config.fee_vault = fee_vault;
     }
 
+    if let Some(rules_limits) = rules_limits {
+        rules_limits.validate_limits()?;
+        config.rules_limits = rules_limits;
+    }
+
+    if let Some(delegation) = delegation {
+        delegation.validate()?;
+        config.delegation = delegation;
+    }
+
+    Ok(())
+}
+
+/// First step of an authority transfer. Passing `None` cancels a pending proposal.
+pub fn propose_platform_authority_handler(
+    ctx: Context<UpdatePlatformConfig>,
+    new_authority: Option<Pubkey>,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+
+    config.pending_authority = new_authority;
+
+    emit!(PlatformAuthorityProposed {
+        platform: config.id,
+        authority: config.authority,
+        pending_authority: new_authority,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcceptPlatformAuthority<'info> {
+    pub new_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"platform", config.id.as_ref()],
+        bump = config.bump,
+        constraint = config.pending_authority == Some(new_authority.key()) @ GameError::Unauthorized,
+    )]
+    pub config: Account<'info, PlatformConfig>,
+}
+
+/// Second step of an authority transfer, signed by the proposed authority
+pub fn accept_platform_authority_handler(ctx: Context<AcceptPlatformAuthority>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+
+    let previous_authority = config.authority;
+    config.authority = ctx.accounts.new_authority.key();
+    config.pending_authority = None;
+
+    emit!(PlatformAuthorityTransferred {
+        platform: config.id,
+        previous_authority,
+        new_authority: config.authority,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn set_platform_paused_handler(ctx: Context<UpdatePlatformConfig>, paused: bool) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+
+    config.paused = paused;
+
+    emit!(PlatformPauseUpdated {
+        platform: config.id,
+        paused,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(mint: Pubkey)]
+pub struct AddAllowedMint<'info> {
+    #[account(mut, constraint = config.authority == admin.key() @ GameError::Unauthorized)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"platform", config.id.as_ref()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, PlatformConfig>,
+
+    #[account(
+        init_if_needed,
+        seeds = [ALLOWED_MINT_SEED, config.id.as_ref(), mint.as_ref()],
+        bump,
+        payer = admin,
+        space = 8 + AllowedMint::INIT_SPACE
+    )]
+    pub allowed_mint: Account<'info, AllowedMint>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Allows a mint for entry fees, or updates its limits when already allowed
+pub fn add_allowed_mint_handler(
+    ctx: Context<AddAllowedMint>,
+    mint: Pubkey,
+    min_entry_fee: u64,
+    max_entry_fee: u64,
+    fee_basis_points_override: Option<u16>,
+) -> Result<()> {
+    require!(
+        min_entry_fee > 0 && min_entry_fee <= max_entry_fee,
+        GameError::InvalidParameter
+    );
+    if let Some(fee) = fee_basis_points_override {
+        require!(
+            fee <= MAX_FEE_BASIS_POINTS,
+            GameError::InvalidFeeBasisPoints
+        );
+    }
+
+    let allowed_mint = &mut ctx.accounts.allowed_mint;
+    let clock = Clock::get()?;
+
+    allowed_mint.platform = ctx.accounts.config.id;
+    allowed_mint.mint = mint;
+    allowed_mint.min_entry_fee = min_entry_fee;
+    allowed_mint.max_entry_fee = max_entry_fee;
+    allowed_mint.fee_basis_points_override = fee_basis_points_override;
+    allowed_mint.bump = ctx.bumps.allowed_mint;
+
+    emit!(AllowedMintAdded {
+        platform: allowed_mint.platform,
+        mint,
+        min_entry_fee,
+        max_entry_fee,
+        fee_basis_points_override,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RemoveAllowedMint<'info> {
+    #[account(mut, constraint = config.authority == admin.key() @ GameError::Unauthorized)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"platform", config.id.as_ref()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        seeds = [ALLOWED_MINT_SEED, config.id.as_ref(), allowed_mint.mint.as_ref()],
+        bump = allowed_mint.bump,
+        close = admin
+    )]
+    pub allowed_mint: Account<'info, AllowedMint>,
+}
+
+/// Stops new games using a mint. Games already created keep their terms.
+pub fn remove_allowed_mint_handler(ctx: Context<RemoveAllowedMint>) -> Result<()> {
+    let allowed_mint = &ctx.accounts.allowed_mint;
+    let clock = Clock::get()?;
+
+    emit!(AllowedMintRemoved {
+        platform: allowed_mint.platform,
+        mint: allowed_mint.mint,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(kind: DeckKind)]
+pub struct InitializeCardDeck<'info> {
+    #[account(mut, constraint = config.authority == admin.key() @ GameError::Unauthorized)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"platform", config.id.as_ref()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, PlatformConfig>,
+
+    #[account(
+        init,
+        seeds = [kind.seed(), config.id.as_ref()],
+        bump,
+        payer = admin,
+        space = 8 + CardDeck::INIT_SPACE
+    )]
+    pub card_deck: Account<'info, CardDeck>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_card_deck_handler(
+    ctx: Context<InitializeCardDeck>,
+    kind: DeckKind,
+) -> Result<()> {
+    let card_deck = &mut ctx.accounts.card_deck;
+    let clock = Clock::get()?;
+
+    card_deck.platform = ctx.accounts.config.id;
+    card_deck.kind = kind;
+    card_deck.cards = match kind {
+        DeckKind::Chance => DEFAULT_CHANCE_CARDS.to_vec(),
+        DeckKind::CommunityChest => DEFAULT_COMMUNITY_CHEST_CARDS.to_vec(),
+    };
+    card_deck.bump = ctx.bumps.card_deck;
+
+    emit!(CardDeckUpdated {
+        platform: card_deck.platform,
+        kind,
+        card_count: card_deck.cards.len() as u8,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(kind: DeckKind)]
+pub struct UpdateCardDeck<'info> {
+    #[account(constraint = config.authority == admin.key() @ GameError::Unauthorized)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"platform", config.id.as_ref()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        seeds = [kind.seed(), config.id.as_ref()],
+        bump = card_deck.bump,
+    )]
+    pub card_deck: Account<'info, CardDeck>,
+}
+
+pub fn update_card_deck_handler(
+    ctx: Context<UpdateCardDeck>,
+    kind: DeckKind,
+    cards: Vec<CardData>,
+) -> Result<()> {
+    validate_deck_cards(&cards)?;
+
+    let card_deck = &mut ctx.accounts.card_deck;
+    let clock = Clock::get()?;
+
+    card_deck.cards = cards;
+
+    emit!(CardDeckUpdated {
+        platform: card_deck.platform,
+        kind,
+        card_count: card_deck.cards.len() as u8,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+fn validate_deck_cards(cards: &[CardData]) -> Result<()> {
+    require!(
+        !cards.is_empty() && cards.len() <= MAX_CARDS_PER_DECK,
+        GameError::InvalidCardDeck
+    );
+
+    let jail_cards = cards
+        .iter()
+        .filter(|card| card.effect_type == CardEffectType::GetOutOfJailFree)
+        .count();
+    require!(
+        jail_cards <= MAX_JAIL_CARDS_PER_DECK,
+        GameError::InvalidCardDeck
+    );
+
+    for card in cards {
+        match card.effect_type {
+            // Negative moves go back, positive ones go to a board position
+            CardEffectType::Move => require!(
+                card.amount.unsigned_abs() < BOARD_SIZE as u32,
+                GameError::InvalidCardDeck
+            ),
+            CardEffectType::PayPerProperty
+            | CardEffectType::CollectFromPlayers
+            | CardEffectType::PayEachPlayer => {
+                require!(card.amount >= 0, GameError::InvalidCardDeck)
+            }
+            _ => {}
+        }
+    }
+
     Ok(())
 }
@@ -1,9 +1,9 @@
 use crate::constants::*;
 use crate::error::GameError;
+use crate::instructions::open_property_auction;
 use crate::state::*;
 use crate::utils::*;
 use anchor_lang::prelude::*;
-use ephemeral_rollups_sdk::cpi::DelegateConfig;
 
 // #[derive(Accounts)]
 // #[instruction(game_key: Pubkey, position: u8)]
@@ -1003,7 +1003,8 @@ pub struct BuyPropertyV2<'info> {
     #[account(
         mut,
         seeds = [b"player", game.key().as_ref(), player.key().as_ref()],
-        bump
+        bump,
+        constraint = !player_state.needs_bankruptcy_check @ GameError::DebtOutstanding
     )]
     pub player_state: Box<Account<'info, PlayerState>>,
 
@@ -1264,7 +1265,8 @@ pub struct DeclinePropertyV2<'info> {
     #[account(
         mut,
         seeds = [b"player", game.key().as_ref(), player.key().as_ref()],
-        bump
+        bump,
+        constraint = !player_state.needs_bankruptcy_check @ GameError::DebtOutstanding
     )]
     pub player_state: Box<Account<'info, PlayerState>>,
 
@@ -1324,15 +1326,8 @@ pub fn decline_property_v2_handler(ctx: Context<DeclinePropertyV2>, position: u8
     // Get static data for logging
     let static_data = get_property_data(position)?;
 
-    // Clear flags
-    player_state.needs_property_action = false;
-    player_state.pending_property_position = None;
-
-    // Update timestamp
-    game.turn_started_at = clock.unix_timestamp;
-
     msg!(
-        "Player {} declined to purchase property {} (${}) - property may go to auction",
+        "Player {} declined to purchase property {} (${}) - property goes to auction",
         player_pubkey,
         position,
         static_data.price
@@ -1346,7 +1341,8 @@ pub fn decline_property_v2_handler(ctx: Context<DeclinePropertyV2>, position: u8
         timestamp: clock.unix_timestamp,
     });
 
-    // Note: Auction system would be triggered here in full implementation
+    // Declined property is auctioned to all remaining players
+    open_property_auction(game, player_state, position, clock)?;
 
     Ok(())
 }
@@ -1364,7 +1360,8 @@ pub struct PayRentV2<'info> {
     #[account(
         mut,
         seeds = [b"player", game.key().as_ref(), payer.key().as_ref()],
-        bump
+        bump,
+        constraint = !payer_state.needs_bankruptcy_check @ GameError::DebtOutstanding
     )]
     pub payer_state: Box<Account<'info, PlayerState>>,
 
@@ -1431,22 +1428,38 @@ pub fn pay_rent_v2_handler(ctx: Context<PayRentV2>, position: u8) -> Result<()>
         return Ok(());
     }
 
+    // House rule: jailed owners don't collect rent
+    if game.rules.no_rent_in_jail && owner_state.in_jail {
+        payer_state.needs_property_action = false;
+        payer_state.double_rent_pending = false;
+        msg!("Owner {} is in jail, no rent collected", owner);
+        return Ok(());
+    }
+
     // Calculate rent
-    let rent_amount = calculate_rent(
+    let mut rent_amount = calculate_rent(
         game,
         position,
         &owner_state.properties_owned,
         payer_state.last_dice_roll,
     )?;
 
+    // Sent here by a card that doubles the rent
+    if payer_state.double_rent_pending {
+        rent_amount = rent_amount
+            .checked_mul(2)
+            .ok_or(GameError::ArithmeticOverflow)?;
+        payer_state.double_rent_pending = false;
+    }
+
     // Check funds
     // require!(
     //     payer_state.cash_balance >= rent_amount,
     //     GameError::InsufficientFunds
     // );
     if payer_state.cash_balance < rent_amount {
-        // Set bankruptcy check flag
-        payer_state.needs_bankruptcy_check = true;
+        // Set bankruptcy check flag, the debt is owed to the property owner
+        payer_state.record_debt(Some(owner), rent_amount)?;
         return Ok(());
     }
 
@@ -1512,7 +1525,8 @@ pub struct BuildHouseV2<'info> {
     #[account(
         mut,
         seeds = [b"player", game.key().as_ref(), player.key().as_ref()],
-        bump
+        bump,
+        constraint = !player_state.needs_bankruptcy_check @ GameError::DebtOutstanding
     )]
     pub player_state: Box<Account<'info, PlayerState>>,
 
@@ -1684,7 +1698,8 @@ pub struct BuildHotelV2<'info> {
     #[account(
         mut,
         seeds = [b"player", game.key().as_ref(), player.key().as_ref()],
-        bump
+        bump,
+        constraint = !player_state.needs_bankruptcy_check @ GameError::DebtOutstanding
     )]
     pub player_state: Box<Account<'info, PlayerState>>,
 
@@ -1903,6 +1918,12 @@ pub fn unmortgage_property_v2_handler(
         GameError::NotPlayerTurn
     );
 
+    // Cash raised while in debt must go to the creditor first
+    require!(
+        !player_state.needs_bankruptcy_check,
+        GameError::DebtOutstanding
+    );
+
     player_state.record_action(clock);
 
     // Get static data
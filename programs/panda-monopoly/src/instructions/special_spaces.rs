@@ -1,6 +1,9 @@
 use crate::error::GameError;
 use crate::state::*;
-use crate::{constants::*, generate_card_index, send_player_to_jail_and_end_turn, ID};
+use crate::{
+    collect_from_players, commit_reveal_seed, constants::*, pay_each_player,
+    send_player_to_jail_and_end_turn, visit_free_parking, ID,
+};
 use anchor_lang::prelude::*;
 use ephemeral_vrf_sdk::anchor::vrf;
 use ephemeral_vrf_sdk::instructions::create_request_randomness_ix;
@@ -90,16 +93,17 @@ pub struct DrawChanceCard<'info> {
     #[account(
         mut,
         seeds = [b"player", game.key().as_ref(), player.key().as_ref()],
-        bump
+        bump,
+        constraint = !player_state.needs_bankruptcy_check @ GameError::DebtOutstanding
     )]
     pub player_state: Box<Account<'info, PlayerState>>,
 
     #[account(mut)]
     pub player: Signer<'info>,
 
-    /// CHECK: This is the recent blockhashes sysvar
-    #[account(address = anchor_lang::solana_program::sysvar::recent_blockhashes::ID)]
-    pub recent_blockhashes: UncheckedAccount<'info>,
+    /// CHECK: This is the slot hashes sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
 
     pub clock: Sysvar<'info, Clock>,
 
@@ -108,11 +112,12 @@ pub struct DrawChanceCard<'info> {
     pub oracle_queue: AccountInfo<'info>,
 }
 
-pub fn draw_chance_card_handler(
-    ctx: Context<DrawChanceCard>,
+pub fn draw_chance_card_handler<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, DrawChanceCard<'info>>,
     use_vrf: bool,
     client_seed: u8,
     card_index: Option<u8>,
+    reveal: Option<[u8; 32]>,
 ) -> Result<()> {
     let game = &mut ctx.accounts.game;
     let player_state = &mut ctx.accounts.player_state;
@@ -133,11 +138,49 @@ pub fn draw_chance_card_handler(
         return Err(GameError::InvalidSpecialSpaceAction.into());
     }
 
+    require!(use_vrf == game.use_vrf, GameError::RandomnessModeMismatch);
+
+    #[cfg(not(feature = "local"))]
+    require!(
+        card_index.is_none(),
+        GameError::RandomnessOverrideNotAllowed
+    );
+
     player_state.record_action(clock);
 
     if use_vrf {
         msg!("Requesting randomness for chance card...");
 
+        let mut accounts_metas = vec![
+            // game
+            SerializableAccountMeta {
+                pubkey: ctx.accounts.game.key(),
+                is_signer: false,
+                is_writable: true,
+            },
+            // player state
+            SerializableAccountMeta {
+                pubkey: ctx.accounts.player_state.key(),
+                is_signer: false,
+                is_writable: true,
+            },
+            // clock
+            SerializableAccountMeta {
+                pubkey: ctx.accounts.clock.key(),
+                is_signer: false,
+                is_writable: false,
+            },
+        ];
+
+        // other players, for cards that move money between players
+        accounts_metas.extend(ctx.remaining_accounts.iter().map(|account| {
+            SerializableAccountMeta {
+                pubkey: account.key(),
+                is_signer: false,
+                is_writable: true,
+            }
+        }));
+
         let ix = create_request_randomness_ix(
             ephemeral_vrf_sdk::instructions::RequestRandomnessParams {
                 payer: ctx.accounts.player.key(),
@@ -146,26 +189,7 @@ pub fn draw_chance_card_handler(
                 callback_discriminator: crate::instruction::CallbackDrawChanceCard::DISCRIMINATOR
                     .to_vec(),
                 caller_seed: [client_seed; 32],
-                accounts_metas: Some(vec![
-                    // game
-                    SerializableAccountMeta {
-                        pubkey: ctx.accounts.game.key(),
-                        is_signer: false,
-                        is_writable: true,
-                    },
-                    // player state
-                    SerializableAccountMeta {
-                        pubkey: ctx.accounts.player_state.key(),
-                        is_signer: false,
-                        is_writable: true,
-                    },
-                    // clock
-                    SerializableAccountMeta {
-                        pubkey: ctx.accounts.clock.key(),
-                        is_signer: false,
-                        is_writable: false,
-                    },
-                ]),
+                accounts_metas: Some(accounts_metas),
                 ..Default::default()
             },
         );
@@ -176,27 +200,26 @@ pub fn draw_chance_card_handler(
         return Ok(());
     }
     // Use provided card index for testing, otherwise generate random
-    let card_index = if let Some(index) = card_index {
-        index as usize
+    let (card_index, card) = if let Some(index) = card_index {
+        let index = index as usize;
+        (index, game.take_card(DeckKind::Chance, index)?)
     } else {
-        // Generate random card index using recent blockhash
-        generate_card_index(
-            &ctx.accounts.recent_blockhashes,
-            clock.unix_timestamp,
-            CHANCE_CARDS.len(),
-        )?
+        // Every draw consumes a fresh reveal, its seed reshuffles the deck when it runs out
+        let reveal = reveal.ok_or(GameError::InvalidRandomnessReveal)?;
+        let seed = commit_reveal_seed(
+            game,
+            player_state,
+            &ctx.accounts.slot_hashes,
+            reveal,
+            clock.slot,
+        )?;
+        game.draw_card(DeckKind::Chance, seed)?
     };
 
     if use_vrf {
         return Ok(());
     }
 
-    if card_index >= CHANCE_CARDS.len() {
-        return Err(GameError::InvalidParameter.into());
-    }
-
-    let card = &CHANCE_CARDS[card_index];
-
     emit!(ChanceCardDrawn {
         player: player_pubkey,
         game: game.key(),
@@ -209,7 +232,14 @@ pub fn draw_chance_card_handler(
     player_state.card_drawn_at = Some(clock.unix_timestamp);
 
     // Execute card effect
-    execute_chance_card_effect(game, player_state, card, clock)?;
+    execute_chance_card_effect(
+        game,
+        player_state,
+        &card,
+        card_index,
+        ctx.remaining_accounts,
+        clock,
+    )?;
 
     // Clear the chance card requirement
     player_state.needs_chance_card = false;
@@ -249,26 +279,20 @@ pub struct CallbackDrawChanceCardCtx<'info> {
     pub clock: Sysvar<'info, Clock>,
 }
 
-pub fn callback_draw_chance_card(
-    ctx: Context<CallbackDrawChanceCardCtx>,
+pub fn callback_draw_chance_card<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, CallbackDrawChanceCardCtx<'info>>,
     randomness: [u8; 32],
 ) -> Result<()> {
-    let card_index =
-        ephemeral_vrf_sdk::rnd::random_u8_with_range(&randomness, 0, CHANCE_CARDS.len() as u8 - 1)
-            as usize;
-    msg!("VRF generated chance card index: {}", card_index);
-
     let game = &mut ctx.accounts.game;
+
+    let seed = u64::from_le_bytes(randomness[..8].try_into().unwrap());
+    let (card_index, card) = game.draw_card(DeckKind::Chance, seed)?;
+    msg!("VRF drew chance card index: {}", card_index);
+
     let player_state = &mut ctx.accounts.player_state;
     let clock = &ctx.accounts.clock;
     let player_pubkey = player_state.wallet;
 
-    if card_index >= CHANCE_CARDS.len() {
-        return Err(GameError::InvalidParameter.into());
-    }
-
-    let card = &CHANCE_CARDS[card_index];
-
     emit!(ChanceCardDrawn {
         player: player_pubkey,
         game: game.key(),
@@ -281,7 +305,14 @@ pub fn callback_draw_chance_card(
     player_state.card_drawn_at = Some(clock.unix_timestamp);
 
     // Execute card effect
-    execute_chance_card_effect(game, player_state, card, clock)?;
+    execute_chance_card_effect(
+        game,
+        player_state,
+        &card,
+        card_index,
+        ctx.remaining_accounts,
+        clock,
+    )?;
 
     // Clear the chance card requirement
     player_state.needs_chance_card = false;
@@ -326,16 +357,17 @@ pub struct DrawCommunityChestCard<'info> {
     #[account(
         mut,
         seeds = [b"player", game.key().as_ref(), player.key().as_ref()],
-        bump
+        bump,
+        constraint = !player_state.needs_bankruptcy_check @ GameError::DebtOutstanding
     )]
     pub player_state: Box<Account<'info, PlayerState>>,
 
     #[account(mut)]
     pub player: Signer<'info>,
 
-    /// CHECK: This is the recent blockhashes sysvar
-    #[account(address = anchor_lang::solana_program::sysvar::recent_blockhashes::ID)]
-    pub recent_blockhashes: UncheckedAccount<'info>,
+    /// CHECK: This is the slot hashes sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
 
     pub clock: Sysvar<'info, Clock>,
 
@@ -344,11 +376,12 @@ pub struct DrawCommunityChestCard<'info> {
     pub oracle_queue: AccountInfo<'info>,
 }
 
-pub fn draw_community_chest_card_handler(
-    ctx: Context<DrawCommunityChestCard>,
+pub fn draw_community_chest_card_handler<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, DrawCommunityChestCard<'info>>,
     use_vrf: bool,
     client_seed: u8,
     card_index: Option<u8>,
+    reveal: Option<[u8; 32]>,
 ) -> Result<()> {
     let game = &mut ctx.accounts.game;
     let player_state = &mut ctx.accounts.player_state;
@@ -372,11 +405,49 @@ pub fn draw_community_chest_card_handler(
         return Err(GameError::InvalidSpecialSpaceAction.into());
     }
 
+    require!(use_vrf == game.use_vrf, GameError::RandomnessModeMismatch);
+
+    #[cfg(not(feature = "local"))]
+    require!(
+        card_index.is_none(),
+        GameError::RandomnessOverrideNotAllowed
+    );
+
     player_state.record_action(clock);
 
     if use_vrf {
         msg!("Requesting randomness for community chest card...");
 
+        let mut accounts_metas = vec![
+            // game
+            SerializableAccountMeta {
+                pubkey: ctx.accounts.game.key(),
+                is_signer: false,
+                is_writable: true,
+            },
+            // player state
+            SerializableAccountMeta {
+                pubkey: ctx.accounts.player_state.key(),
+                is_signer: false,
+                is_writable: true,
+            },
+            // clock
+            SerializableAccountMeta {
+                pubkey: ctx.accounts.clock.key(),
+                is_signer: false,
+                is_writable: false,
+            },
+        ];
+
+        // other players, for cards that move money between players
+        accounts_metas.extend(ctx.remaining_accounts.iter().map(|account| {
+            SerializableAccountMeta {
+                pubkey: account.key(),
+                is_signer: false,
+                is_writable: true,
+            }
+        }));
+
         let ix = create_request_randomness_ix(
             ephemeral_vrf_sdk::instructions::RequestRandomnessParams {
                 payer: ctx.accounts.player.key(),
@@ -385,26 +456,7 @@ pub fn draw_community_chest_card_handler(
                 callback_discriminator:
                     crate::instruction::CallbackDrawCommunityChestCard::DISCRIMINATOR.to_vec(),
                 caller_seed: [client_seed; 32],
-                accounts_metas: Some(vec![
-                    // game
-                    SerializableAccountMeta {
-                        pubkey: ctx.accounts.game.key(),
-                        is_signer: false,
-                        is_writable: true,
-                    },
-                    // player state
-                    SerializableAccountMeta {
-                        pubkey: ctx.accounts.player_state.key(),
-                        is_signer: false,
-                        is_writable: true,
-                    },
-                    // clock
-                    SerializableAccountMeta {
-                        pubkey: ctx.accounts.clock.key(),
-                        is_signer: false,
-                        is_writable: false,
-                    },
-                ]),
+                accounts_metas: Some(accounts_metas),
                 ..Default::default()
             },
         );
@@ -415,24 +467,23 @@ pub fn draw_community_chest_card_handler(
         return Ok(());
     }
 
-    // Use provided card index for testing, otherwise generate random using recent blockhash
-    let card_index = if let Some(index) = card_index {
-        index as usize
+    // Use provided card index for testing, otherwise generate random
+    let (card_index, card) = if let Some(index) = card_index {
+        let index = index as usize;
+        (index, game.take_card(DeckKind::CommunityChest, index)?)
     } else {
-        // Generate random card index using recent blockhash
-        generate_card_index(
-            &ctx.accounts.recent_blockhashes,
-            clock.unix_timestamp,
-            COMMUNITY_CHEST_CARDS.len(),
-        )?
+        // Every draw consumes a fresh reveal, its seed reshuffles the deck when it runs out
+        let reveal = reveal.ok_or(GameError::InvalidRandomnessReveal)?;
+        let seed = commit_reveal_seed(
+            game,
+            player_state,
+            &ctx.accounts.slot_hashes,
+            reveal,
+            clock.slot,
+        )?;
+        game.draw_card(DeckKind::CommunityChest, seed)?
     };
 
-    if card_index >= COMMUNITY_CHEST_CARDS.len() {
-        return Err(GameError::InvalidParameter.into());
-    }
-
-    let card = &COMMUNITY_CHEST_CARDS[card_index];
-
     emit!(CommunityChestCardDrawn {
         player: player_pubkey,
         game: game.key(),
@@ -445,7 +496,14 @@ pub fn draw_community_chest_card_handler(
     player_state.card_drawn_at = Some(clock.unix_timestamp);
 
     // Execute card effect
-    execute_community_chest_card_effect(game, player_state, card, clock)?;
+    execute_community_chest_card_effect(
+        game,
+        player_state,
+        &card,
+        card_index,
+        ctx.remaining_accounts,
+        clock,
+    )?;
 
     // Clear the community chest card requirement
     player_state.needs_community_chest_card = false;
@@ -489,28 +547,20 @@ pub struct CallbackDrawCommunityChestCardCtx<'info> {
     pub clock: Sysvar<'info, Clock>,
 }
 
-pub fn callback_draw_community_chest_card(
-    ctx: Context<CallbackDrawCommunityChestCardCtx>,
+pub fn callback_draw_community_chest_card<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, CallbackDrawCommunityChestCardCtx<'info>>,
     randomness: [u8; 32],
 ) -> Result<()> {
-    let card_index = ephemeral_vrf_sdk::rnd::random_u8_with_range(
-        &randomness,
-        0,
-        COMMUNITY_CHEST_CARDS.len() as u8 - 1,
-    ) as usize;
-    msg!("VRF generated community chest card index: {}", card_index);
-
     let game = &mut ctx.accounts.game;
+
+    let seed = u64::from_le_bytes(randomness[..8].try_into().unwrap());
+    let (card_index, card) = game.draw_card(DeckKind::CommunityChest, seed)?;
+    msg!("VRF drew community chest card index: {}", card_index);
+
     let player_state = &mut ctx.accounts.player_state;
     let clock = &ctx.accounts.clock;
     let player_pubkey = player_state.wallet;
 
-    if card_index >= COMMUNITY_CHEST_CARDS.len() {
-        return Err(GameError::InvalidParameter.into());
-    }
-
-    let card = &COMMUNITY_CHEST_CARDS[card_index];
-
     emit!(CommunityChestCardDrawn {
         player: player_pubkey,
         game: game.key(),
@@ -523,7 +573,14 @@ pub fn callback_draw_community_chest_card(
     player_state.card_drawn_at = Some(clock.unix_timestamp);
 
     // Execute card effect
-    execute_community_chest_card_effect(game, player_state, card, clock)?;
+    execute_community_chest_card_effect(
+        game,
+        player_state,
+        &card,
+        card_index,
+        ctx.remaining_accounts,
+        clock,
+    )?;
 
     // Clear the community chest card requirement
     player_state.needs_community_chest_card = false;
@@ -552,10 +609,12 @@ pub fn callback_draw_community_chest_card(
 }
 
 // Helper function to execute chance card effects
-fn execute_chance_card_effect(
+fn execute_chance_card_effect<'info>(
     game: &mut Box<Account<'_, GameState>>,
     player_state: &mut Box<Account<'_, PlayerState>>,
-    card: &ChanceCard,
+    card: &CardData,
+    card_index: usize,
+    remaining_accounts: &'info [AccountInfo<'info>],
     clock: &Sysvar<Clock>,
 ) -> Result<()> {
     match card.effect_type {
@@ -564,13 +623,7 @@ fn execute_chance_card_effect(
                 player_state.cash_balance += card.amount as u64;
             } else {
                 let deduction = (-card.amount) as u64;
-                if player_state.cash_balance >= deduction {
-                    player_state.cash_balance -= deduction;
-                } else {
-                    // FIXME  có cần phải set balance = 0 k?
-                    player_state.cash_balance = 0;
-                    player_state.needs_bankruptcy_check = true;
-                }
+                pay_bank(game, player_state, deduction)?;
             }
         }
         CardEffectType::Move => {
@@ -591,12 +644,13 @@ fn execute_chance_card_effect(
 
             // Check if passing GO (only for forward movement)
             if card.amount >= 0 && (new_position < old_position || new_position == 0) {
-                player_state.cash_balance += GO_SALARY as u64;
+                let go_salary = game.rules.go_salary_for(new_position);
+                player_state.cash_balance += go_salary;
 
                 emit!(PlayerPassedGo {
                     player: player_state.wallet,
                     game: game.key(),
-                    salary_collected: GO_SALARY as u64,
+                    salary_collected: go_salary,
                     new_position,
                     timestamp: clock.unix_timestamp,
                 });
@@ -610,6 +664,9 @@ fn execute_chance_card_effect(
                     player_state.needs_property_action = true;
                     player_state.pending_property_position = Some(new_position);
                 }
+            } else if new_position == FREE_PARKING_POSITION {
+                let game_key = game.key();
+                visit_free_parking(game, game_key, player_state, clock)?;
             } else {
                 player_state.needs_special_space_action = true;
                 player_state.pending_special_space_position = Some(new_position);
@@ -617,101 +674,55 @@ fn execute_chance_card_effect(
         }
         CardEffectType::MoveToNearest => {
             // Move to nearest memecoin property (BONK or WIF)
-            let current_pos = player_state.position;
-            let bonk_pos = 1; // BONK Avenue position
-            let wif_pos = 3; // WIF Lane position
-
-            // Calculate distances to both memecoin properties
-            let dist_to_bonk = if bonk_pos > current_pos {
-                bonk_pos - current_pos
-            } else {
-                40 - current_pos + bonk_pos
-            };
-
-            let dist_to_wif = if wif_pos > current_pos {
-                wif_pos - current_pos
-            } else {
-                40 - current_pos + wif_pos
-            };
-
-            // Move to the nearest one
-            let new_position = if dist_to_bonk <= dist_to_wif {
-                bonk_pos
-            } else {
-                wif_pos
-            };
-
-            let old_position = player_state.position;
-
-            // Check if passing GO
-            if new_position < old_position {
-                player_state.cash_balance += GO_SALARY as u64;
-
-                emit!(PlayerPassedGo {
-                    player: player_state.wallet,
-                    game: game.key(),
-                    salary_collected: GO_SALARY as u64,
-                    new_position,
-                    timestamp: clock.unix_timestamp,
-                });
-            }
-
-            player_state.position = new_position;
-
-            if !player_state.properties_owned.contains(&new_position) {
-                player_state.needs_property_action = true;
-                player_state.pending_property_position = Some(new_position);
-                msg!(
-                    "Player landed on unowned property at position {}",
-                    new_position
-                );
-            }
+            move_to_nearest(game, player_state, &BROWN_GROUP, false, clock)?;
         }
         CardEffectType::GoToJail => {
             send_player_to_jail_and_end_turn(game, player_state, clock);
             return Ok(()); // Early return since turn is ended
         }
         CardEffectType::GetOutOfJailFree => {
-            player_state.get_out_of_jail_cards += 1;
+            player_state.get_out_of_jail_cards.push(HeldJailCard {
+                deck: DeckKind::Chance,
+                card_index: card_index as u8,
+            });
         }
         CardEffectType::PayPerProperty => {
-            // Calculate repair costs: $25 per house, $100 per hotel
-            let mut total_cost = 0u64;
-
-            // Count houses and hotels owned by player using GameState properties
-            for &property_pos in &player_state.properties_owned {
-                if let Ok(property_data) = get_property_data(property_pos) {
-                    if property_data.property_type == PropertyType::Street {
-                        // Get actual property info from GameState
-                        let property = game.get_property(property_pos)?;
-
-                        // Calculate cost based on actual buildings
-                        if property.has_hotel {
-                            total_cost += 100; // $100 per hotel
-                        } else {
-                            total_cost += property.houses as u64 * 25; // $25 per house
-                        }
-                    }
-                }
-            }
-
-            if player_state.cash_balance >= total_cost {
-                player_state.cash_balance -= total_cost;
-            } else {
-                player_state.cash_balance = 0;
-                player_state.needs_bankruptcy_check = true;
-            }
+            let total_cost = player_state.properties_owned.len() as u64 * card.amount as u64;
+            pay_bank(game, player_state, total_cost)?;
         }
         CardEffectType::CollectFromPlayers => {
             // Collect money from all other players
-            let amount_per_player = card.amount as u64;
-            let total_players = game.players.len() as u64;
-            let total_collected = amount_per_player * (total_players - 1); // Exclude current player
+            let game_key = game.key();
+            let total_collected = collect_from_players(
+                game,
+                &game_key,
+                player_state,
+                remaining_accounts,
+                card.amount as u64,
+            )?;
 
-            player_state.cash_balance += total_collected;
+            msg!(
+                "Player {} collected ${} from other players",
+                player_state.wallet,
+                total_collected
+            );
+        }
+        CardEffectType::PayEachPlayer => {
+            // Pay every other player
+            let game_key = game.key();
+            let total_paid = pay_each_player(
+                game,
+                &game_key,
+                player_state,
+                remaining_accounts,
+                card.amount as u64,
+            )?;
 
-            // Note: In a full implementation, you'd need to deduct from other players
-            // This would require iterating through all player states
+            msg!(
+                "Player {} paid ${} to other players",
+                player_state.wallet,
+                total_paid
+            );
         }
         CardEffectType::RepairFree => {
             // Free repairs - no cost for property maintenance
@@ -721,16 +732,31 @@ fn execute_chance_card_effect(
                 player_state.wallet
             );
         }
+        CardEffectType::MoveToNearestRailroad => {
+            move_to_nearest(game, player_state, &RAILROAD_GROUP, true, clock)?;
+        }
+        CardEffectType::MoveToNearestUtility => {
+            move_to_nearest(game, player_state, &UTILITY_GROUP, true, clock)?;
+        }
+        CardEffectType::AdvanceToGo => {
+            advance_player_to(game, player_state, GO_POSITION, clock)?;
+        }
+        CardEffectType::StreetRepairs => {
+            let total_cost = calculate_street_repairs(game, player_state, card)?;
+            pay_bank(game, player_state, total_cost)?;
+        }
     }
 
     Ok(())
 }
 
 // Helper function to execute community chest card effects
-fn execute_community_chest_card_effect(
+fn execute_community_chest_card_effect<'info>(
     game: &mut Box<Account<'_, GameState>>,
     player_state: &mut Box<Account<'_, PlayerState>>,
-    card: &CommunityChestCard,
+    card: &CardData,
+    card_index: usize,
+    remaining_accounts: &'info [AccountInfo<'info>],
     clock: &Sysvar<Clock>,
 ) -> Result<()> {
     match card.effect_type {
@@ -739,13 +765,7 @@ fn execute_community_chest_card_effect(
                 player_state.cash_balance += card.amount as u64;
             } else {
                 let deduction = (-card.amount) as u64;
-                if player_state.cash_balance >= deduction {
-                    player_state.cash_balance -= deduction;
-                } else {
-                    // FIXME  có cần phải set balance = 0 k?
-                    player_state.cash_balance = 0;
-                    player_state.needs_bankruptcy_check = true;
-                }
+                pay_bank(game, player_state, deduction)?;
             }
         }
         CardEffectType::Move => {
@@ -754,12 +774,13 @@ fn execute_community_chest_card_effect(
 
             // Check if passing GO
             if new_position < old_position || new_position == 0 {
-                player_state.cash_balance += GO_SALARY as u64;
+                let go_salary = game.rules.go_salary_for(new_position);
+                player_state.cash_balance += go_salary;
 
                 emit!(PlayerPassedGo {
                     player: player_state.wallet,
                     game: game.key(),
-                    salary_collected: GO_SALARY as u64,
+                    salary_collected: go_salary,
                     new_position,
                     timestamp: clock.unix_timestamp,
                 });
@@ -767,6 +788,11 @@ fn execute_community_chest_card_effect(
 
             player_state.position = new_position;
 
+            if new_position == FREE_PARKING_POSITION {
+                let game_key = game.key();
+                visit_free_parking(game, game_key, player_state, clock)?;
+            }
+
             // Set flags for handling the new space
             // if is_property_purchasable(new_position) {
             //     player_state.needs_property_action = true;
@@ -794,77 +820,56 @@ fn execute_community_chest_card_effect(
             }
         }
         CardEffectType::MoveToNearest => {
-            // Not used in community chest cards, but included for completeness
-            msg!("MoveToNearest effect not implemented for community chest cards");
+            // Move to nearest memecoin property (BONK or WIF)
+            move_to_nearest(game, player_state, &BROWN_GROUP, false, clock)?;
         }
         CardEffectType::GoToJail => {
             send_player_to_jail_and_end_turn(game, player_state, clock);
             return Ok(()); // Early return since turn is ended
         }
         CardEffectType::GetOutOfJailFree => {
-            player_state.get_out_of_jail_cards += 1;
+            player_state.get_out_of_jail_cards.push(HeldJailCard {
+                deck: DeckKind::CommunityChest,
+                card_index: card_index as u8,
+            });
         }
         CardEffectType::PayPerProperty => {
-            // // Calculate street repair costs: $40 per house, $115 per hotel
-            // let mut total_cost = 0u64;
-
-            // // Count houses and hotels owned by player
-            // for &property_pos in &player_state.properties_owned {
-            //     // FIXME
-            //     if let Ok(property_data) = get_property_data(property_pos) {
-            //         if property_data.property_type == PropertyType::Street {
-            //             // Street property
-            //             // This would need access to PropertyState to count actual houses/hotels
-            //             // For now, we'll use a simplified calculation
-            //             total_cost += 40; // Assume 1 house per property for simplification
-            //         }
-            //     }
-            // }
-
-            // if player_state.cash_balance >= total_cost {
-            //     player_state.cash_balance -= total_cost;
-            // } else {
-            //     player_state.cash_balance = 0;
-            //     player_state.needs_bankruptcy_check = true;
-            // }
-
-            // Calculate street repair costs: $40 per house, $115 per hotel
-            let mut total_cost = 0u64;
-
-            // Count houses and hotels owned by player using GameState properties
-            for &property_pos in &player_state.properties_owned {
-                if let Ok(property_data) = get_property_data(property_pos) {
-                    if property_data.property_type == PropertyType::Street {
-                        // Get actual property info from GameState
-                        let property = game.get_property(property_pos)?;
-
-                        // Calculate cost based on actual buildings
-                        if property.has_hotel {
-                            total_cost += 115; // $115 per hotel
-                        } else {
-                            total_cost += property.houses as u64 * 40; // $40 per house
-                        }
-                    }
-                }
-            }
-
-            if player_state.cash_balance >= total_cost {
-                player_state.cash_balance -= total_cost;
-            } else {
-                player_state.cash_balance = 0;
-                player_state.needs_bankruptcy_check = true;
-            }
+            let total_cost = player_state.properties_owned.len() as u64 * card.amount as u64;
+            pay_bank(game, player_state, total_cost)?;
         }
         CardEffectType::CollectFromPlayers => {
             // Collect money from all other players (birthday card)
-            let amount_per_player = card.amount as u64;
-            let total_players = game.players.len() as u64;
-            let total_collected = amount_per_player * (total_players - 1); // Exclude current player
+            let game_key = game.key();
+            let total_collected = collect_from_players(
+                game,
+                &game_key,
+                player_state,
+                remaining_accounts,
+                card.amount as u64,
+            )?;
 
-            player_state.cash_balance += total_collected;
+            msg!(
+                "Player {} collected ${} from other players",
+                player_state.wallet,
+                total_collected
+            );
+        }
+        CardEffectType::PayEachPlayer => {
+            // Pay every other player
+            let game_key = game.key();
+            let total_paid = pay_each_player(
+                game,
+                &game_key,
+                player_state,
+                remaining_accounts,
+                card.amount as u64,
+            )?;
 
-            // Note: In a full implementation, you'd need to deduct from other players
-            // This would require iterating through all player states
+            msg!(
+                "Player {} paid ${} to other players",
+                player_state.wallet,
+                total_paid
+            );
         }
         CardEffectType::RepairFree => {
             // Free repairs for all properties - DAO vote win effect
@@ -873,6 +878,119 @@ fn execute_community_chest_card_effect(
                 player_state.wallet
             );
         }
+        CardEffectType::MoveToNearestRailroad => {
+            move_to_nearest(game, player_state, &RAILROAD_GROUP, true, clock)?;
+        }
+        CardEffectType::MoveToNearestUtility => {
+            move_to_nearest(game, player_state, &UTILITY_GROUP, true, clock)?;
+        }
+        CardEffectType::AdvanceToGo => {
+            advance_player_to(game, player_state, GO_POSITION, clock)?;
+        }
+        CardEffectType::StreetRepairs => {
+            let total_cost = calculate_street_repairs(game, player_state, card)?;
+            pay_bank(game, player_state, total_cost)?;
+        }
+    }
+
+    Ok(())
+}
+
+// Moves the player forward to the closest of `positions`. Double rent is
+// owed if another player owns the property there.
+fn move_to_nearest(
+    game: &mut Box<Account<'_, GameState>>,
+    player_state: &mut Box<Account<'_, PlayerState>>,
+    positions: &[u8],
+    double_rent: bool,
+    clock: &Sysvar<Clock>,
+) -> Result<()> {
+    let current_pos = player_state.position;
+    let new_position = positions
+        .iter()
+        .copied()
+        .min_by_key(|&position| (position + BOARD_SIZE - current_pos - 1) % BOARD_SIZE)
+        .ok_or(GameError::InvalidBoardPosition)?;
+
+    advance_player_to(game, player_state, new_position, clock)?;
+
+    let property = game.get_property(new_position)?;
+    if double_rent && property.owner.is_some() && property.owner != Some(player_state.wallet) {
+        player_state.double_rent_pending = true;
+    }
+
+    Ok(())
+}
+
+// Moves the player forward to `new_position`, collecting GO salary on the way
+fn advance_player_to(
+    game: &mut Box<Account<'_, GameState>>,
+    player_state: &mut Box<Account<'_, PlayerState>>,
+    new_position: u8,
+    clock: &Sysvar<Clock>,
+) -> Result<()> {
+    if new_position <= player_state.position {
+        let go_salary = game.rules.go_salary_for(new_position);
+        player_state.cash_balance += go_salary;
+
+        emit!(PlayerPassedGo {
+            player: player_state.wallet,
+            game: game.key(),
+            salary_collected: go_salary,
+            new_position,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+
+    player_state.position = new_position;
+
+    // Set flags for handling the new space
+    if is_property_purchasable(new_position) {
+        let property = game.get_property(new_position)?;
+        if property.owner != Some(player_state.wallet) {
+            player_state.needs_property_action = true;
+            player_state.pending_property_position = Some(new_position);
+        }
+    } else if new_position == FREE_PARKING_POSITION {
+        let game_key = game.key();
+        visit_free_parking(game, game_key, player_state, clock)?;
+    } else if new_position != GO_POSITION {
+        player_state.needs_special_space_action = true;
+        player_state.pending_special_space_position = Some(new_position);
+    }
+
+    Ok(())
+}
+
+// Street repairs: the card sets the cost per house and per hotel
+fn calculate_street_repairs(
+    game: &GameState,
+    player_state: &PlayerState,
+    card: &CardData,
+) -> Result<u64> {
+    let mut total_cost = 0u64;
+
+    for &property_pos in &player_state.properties_owned {
+        let property = game.get_property(property_pos)?;
+
+        if property.has_hotel {
+            total_cost += card.hotel_cost as u64;
+        } else {
+            total_cost += property.houses as u64 * card.house_cost as u64;
+        }
+    }
+
+    Ok(total_cost)
+}
+
+// Takes a payment owed to the bank, flagging any shortfall as debt
+fn pay_bank(game: &mut GameState, player_state: &mut PlayerState, amount: u64) -> Result<()> {
+    let paid = amount.min(player_state.cash_balance);
+    player_state.cash_balance -= paid;
+    game.add_to_free_parking(paid);
+
+    if paid < amount {
+        player_state.record_debt(None, amount - paid)?;
     }
 
     Ok(())
@@ -893,7 +1011,8 @@ pub struct PayTax<'info> {
     #[account(
         mut,
         seeds = [b"player", game.key().as_ref(), player.key().as_ref()],
-        bump
+        bump,
+        constraint = !player_state.needs_bankruptcy_check @ GameError::DebtOutstanding
     )]
     pub player_state: Box<Account<'info, PlayerState>>,
 
@@ -925,24 +1044,27 @@ pub fn pay_mev_tax_handler(ctx: Context<PayTax>) -> Result<()> {
 
     player_state.record_action(clock);
 
-    if player_state.cash_balance >= MEV_TAX as u64 {
+    let mev_tax = game.rules.mev_tax;
+
+    if player_state.cash_balance >= mev_tax {
         player_state.cash_balance = player_state
             .cash_balance
-            .checked_sub(MEV_TAX as u64)
+            .checked_sub(mev_tax)
             .ok_or(GameError::ArithmeticUnderflow)?;
+        game.add_to_free_parking(mev_tax);
 
         player_state.needs_special_space_action = false;
         player_state.pending_special_space_position = None;
 
-        msg!("Player {} paid MEV tax of ${}", player_pubkey, MEV_TAX);
+        msg!("Player {} paid MEV tax of ${}", player_pubkey, mev_tax);
     } else {
         // Player doesn't have enough money - trigger bankruptcy check
-        player_state.needs_bankruptcy_check = true;
+        player_state.record_debt(None, mev_tax)?;
 
         msg!(
             "Player {} cannot afford MEV tax of ${}. Bankruptcy check required.",
             player_pubkey,
-            MEV_TAX
+            mev_tax
         );
 
         return Ok(());
@@ -955,7 +1077,7 @@ pub fn pay_mev_tax_handler(ctx: Context<PayTax>) -> Result<()> {
         game: game.key(),
         player: player_pubkey,
         tax_type: 1, // 1=mev Tax
-        amount: MEV_TAX as u64,
+        amount: mev_tax,
         position: MEV_TAX_POSITION,
         timestamp: clock.unix_timestamp,
     });
@@ -987,12 +1109,15 @@ pub fn pay_priority_fee_tax_handler(ctx: Context<PayTax>) -> Result<()> {
     }
 
     // Check if player has sufficient funds to pay priority fee tax
-    if player_state.cash_balance >= PRIORITY_FEE_TAX as u64 {
+    let priority_fee_tax = game.rules.priority_fee_tax;
+
+    if player_state.cash_balance >= priority_fee_tax {
         // Deduct priority fee tax from player's cash balance
         player_state.cash_balance = player_state
             .cash_balance
-            .checked_sub(PRIORITY_FEE_TAX as u64)
+            .checked_sub(priority_fee_tax)
             .ok_or(GameError::ArithmeticUnderflow)?;
+        game.add_to_free_parking(priority_fee_tax);
 
         // Clear any pending special space action
         player_state.needs_special_space_action = false;
@@ -1001,19 +1126,19 @@ pub fn pay_priority_fee_tax_handler(ctx: Context<PayTax>) -> Result<()> {
         msg!(
             "Player {} paid priority fee tax of ${}",
             player_pubkey,
-            PRIORITY_FEE_TAX
+            priority_fee_tax
         );
     } else {
         // Player doesn't have enough money - trigger bankruptcy check
-        player_state.needs_bankruptcy_check = true;
+        player_state.record_debt(None, priority_fee_tax)?;
 
         msg!(
             "Player {} cannot afford priority fee tax of ${}. Bankruptcy check required.",
             player_pubkey,
-            PRIORITY_FEE_TAX
+            priority_fee_tax
         );
 
-        return Err(GameError::InsufficientFunds.into());
+        return Ok(());
     }
 
     // Update game timestamp
@@ -1023,10 +1148,106 @@ pub fn pay_priority_fee_tax_handler(ctx: Context<PayTax>) -> Result<()> {
         game: game.key(),
         player: player_pubkey,
         tax_type: 2, // 2=Priority Fee Tax
-        amount: PRIORITY_FEE_TAX as u64,
+        amount: priority_fee_tax,
         position: PRIORITY_FEE_TAX_POSITION,
         timestamp: clock.unix_timestamp,
     });
 
     Ok(())
 }
+
+// -----------------------------------------------------------------------------
+#[derive(Accounts)]
+pub struct HostFestival<'info> {
+    #[account(
+        mut,
+        seeds = [b"game", game.config_id.as_ref(), &game.game_id.to_le_bytes().as_ref()],
+        bump = game.bump,
+        constraint = game.game_status == GameStatus::InProgress @ GameError::GameNotInProgress
+    )]
+    pub game: Box<Account<'info, GameState>>,
+
+    #[account(
+        mut,
+        seeds = [b"player", game.key().as_ref(), player.key().as_ref()],
+        bump,
+        constraint = !player_state.needs_bankruptcy_check @ GameError::DebtOutstanding
+    )]
+    pub player_state: Box<Account<'info, PlayerState>>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn host_festival_handler(ctx: Context<HostFestival>, property_position: u8) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let player_state = &mut ctx.accounts.player_state;
+    let player_pubkey = ctx.accounts.player.key();
+    let clock = &ctx.accounts.clock;
+
+    let player_index = game
+        .players
+        .iter()
+        .position(|&p| p == player_pubkey)
+        .ok_or(GameError::PlayerNotFound)?;
+
+    if game.current_turn != player_index as u8 {
+        return Err(GameError::NotPlayerTurn.into());
+    }
+
+    // Only on the roll that landed on the festival square
+    if !player_state.has_rolled_dice
+        || player_state.position != FESTIVAL_POSITION
+        || !player_state.needs_special_space_action
+        || player_state.pending_special_space_position != Some(FESTIVAL_POSITION)
+    {
+        return Err(GameError::InvalidSpecialSpaceAction.into());
+    }
+
+    // The festival goes on one of the host's own streets
+    let property_data = get_property_data(property_position)?;
+    require!(
+        property_data.property_type == PropertyType::Street,
+        GameError::InvalidPropertyPosition
+    );
+
+    let property = game.get_property(property_position)?;
+    require!(
+        property.owner == Some(player_pubkey),
+        GameError::PropertyNotOwnedByPlayer
+    );
+    require!(!property.is_mortgaged, GameError::PropertyMortgaged);
+
+    player_state.record_action(clock);
+
+    // A new festival replaces any running one
+    game.festival = Some(FestivalInfo {
+        property_position,
+        host: player_pubkey,
+    });
+    player_state.festival_boost_turns = FESTIVAL_BOOST_TURNS;
+
+    player_state.needs_special_space_action = false;
+    player_state.pending_special_space_position = None;
+
+    game.turn_started_at = clock.unix_timestamp;
+
+    emit!(FestivalStarted {
+        game: game.key(),
+        host: player_pubkey,
+        property_position,
+        rent_multiplier: FESTIVAL_RENT_MULTIPLIER,
+        turns: FESTIVAL_BOOST_TURNS,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "Player {} is hosting a festival on property {}",
+        player_pubkey,
+        property_position
+    );
+
+    Ok(())
+}
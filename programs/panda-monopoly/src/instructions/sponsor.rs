@@ -0,0 +1,249 @@
+use crate::constants::*;
+use crate::error::GameError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+#[derive(Accounts)]
+pub struct SponsorGame<'info> {
+    #[account(
+        mut,
+        seeds = [b"game", game.config_id.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump,
+        constraint = game.game_status == GameStatus::WaitingForPlayers @ GameError::GameAlreadyStarted,
+        constraint = game.uses_token_vault() @ GameError::GameNotSponsorable,
+    )]
+    pub game: Box<Account<'info, GameState>>,
+
+    #[account(
+        init_if_needed,
+        payer = sponsor,
+        space = 8 + Sponsorship::INIT_SPACE,
+        seeds = [SPONSORSHIP_SEED, game.key().as_ref(), sponsor.key().as_ref()],
+        bump
+    )]
+    pub sponsorship: Box<Account<'info, Sponsorship>>,
+
+    #[account(mut)]
+    pub sponsor: Signer<'info>,
+
+    #[account(
+        seeds = [b"platform", game.config_id.as_ref()],
+        bump = config.bump,
+        constraint = !config.paused @ GameError::PlatformPaused,
+    )]
+    pub config: Box<Account<'info, PlatformConfig>>,
+
+    #[account(
+        address = game.token_mint.unwrap_or_default() @ GameError::InvalidTokenAccount,
+        mint::token_program = token_program,
+    )]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = sponsor,
+        token::token_program = token_program,
+    )]
+    pub sponsor_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        address = game.token_vault.unwrap_or_default() @ GameError::InvalidTokenVault,
+    )]
+    pub token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+
+    pub clock: Sysvar<'info, Clock>,
+}
+
+/// Adds to a game's prize pool before it starts. Repeat deposits from the
+/// same sponsor top up their existing sponsorship.
+pub fn sponsor_game_handler(ctx: Context<SponsorGame>, amount: u64) -> Result<()> {
+    require!(amount > 0, GameError::InvalidParameter);
+
+    let game = &mut ctx.accounts.game;
+    let sponsorship = &mut ctx.accounts.sponsorship;
+    let token_vault = &mut ctx.accounts.token_vault;
+    let clock = &ctx.accounts.clock;
+
+    let transfer_accounts = TransferChecked {
+        from: ctx.accounts.sponsor_token_account.to_account_info(),
+        mint: ctx.accounts.token_mint.to_account_info(),
+        to: token_vault.to_account_info(),
+        authority: ctx.accounts.sponsor.to_account_info(),
+    };
+
+    let transfer_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        transfer_accounts,
+    );
+
+    let vault_balance_before = token_vault.amount;
+    transfer_checked(transfer_ctx, amount, ctx.accounts.token_mint.decimals)?;
+    token_vault.reload()?;
+
+    // Transfer-fee mints withhold part of the deposit, only credit what arrived
+    let received = token_vault
+        .amount
+        .checked_sub(vault_balance_before)
+        .ok_or(GameError::ArithmeticUnderflow)?;
+
+    if sponsorship.sponsor == Pubkey::default() {
+        sponsorship.game = game.key();
+        sponsorship.sponsor = ctx.accounts.sponsor.key();
+        sponsorship.bump = ctx.bumps.sponsorship;
+
+        game.sponsor_count = game
+            .sponsor_count
+            .checked_add(1)
+            .ok_or(GameError::ArithmeticOverflow)?;
+    }
+
+    sponsorship.amount = sponsorship
+        .amount
+        .checked_add(received)
+        .ok_or(GameError::ArithmeticOverflow)?;
+
+    game.sponsor_pool = game
+        .sponsor_pool
+        .checked_add(received)
+        .ok_or(GameError::ArithmeticOverflow)?;
+    game.total_prize_pool = game
+        .total_prize_pool
+        .checked_add(received)
+        .ok_or(GameError::ArithmeticOverflow)?;
+
+    emit!(GameSponsored {
+        game: game.key(),
+        sponsor: sponsorship.sponsor,
+        amount: received,
+        sponsor_pool: game.sponsor_pool,
+        total_prize_pool: game.total_prize_pool,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "Sponsor {} added {} to game {}",
+        sponsorship.sponsor,
+        received,
+        game.game_id
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RefundSponsor<'info> {
+    #[account(
+        mut,
+        seeds = [b"game", game.config_id.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump,
+        constraint = game.game_status == GameStatus::Cancelling @ GameError::GameNotInProgress,
+    )]
+    pub game: Box<Account<'info, GameState>>,
+
+    #[account(
+        mut,
+        seeds = [SPONSORSHIP_SEED, game.key().as_ref(), sponsor.key().as_ref()],
+        bump = sponsorship.bump,
+        close = sponsor,
+    )]
+    pub sponsorship: Box<Account<'info, Sponsorship>>,
+
+    /// CHECK: receives the sponsorship rent, verified by the sponsorship seeds
+    #[account(mut)]
+    pub sponsor: UncheckedAccount<'info>,
+
+    /// CHECK: game authority PDA
+    #[account(
+        seeds = [GAME_AUTHORITY_SEED],
+        bump,
+    )]
+    pub game_authority: UncheckedAccount<'info>,
+
+    #[account(
+        address = game.token_mint.unwrap_or_default() @ GameError::InvalidTokenAccount,
+        mint::token_program = token_program,
+    )]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = sponsor,
+        token::token_program = token_program,
+    )]
+    pub sponsor_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        address = game.token_vault.unwrap_or_default() @ GameError::InvalidTokenVault,
+    )]
+    pub token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub clock: Sysvar<'info, Clock>,
+}
+
+/// Returns a sponsor's contribution once the game is being cancelled. Anyone
+/// can crank this, the tokens and rent always go back to the sponsor.
+pub fn refund_sponsor_handler(ctx: Context<RefundSponsor>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let amount = ctx.accounts.sponsorship.amount;
+    let clock = &ctx.accounts.clock;
+
+    let authority_seeds = &[GAME_AUTHORITY_SEED, &[ctx.bumps.game_authority]];
+    let signer_seeds = &[&authority_seeds[..]];
+
+    let transfer_accounts = TransferChecked {
+        from: ctx.accounts.token_vault.to_account_info(),
+        mint: ctx.accounts.token_mint.to_account_info(),
+        to: ctx.accounts.sponsor_token_account.to_account_info(),
+        authority: ctx.accounts.game_authority.to_account_info(),
+    };
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        transfer_accounts,
+        signer_seeds,
+    );
+
+    transfer_checked(transfer_ctx, amount, ctx.accounts.token_mint.decimals)?;
+
+    game.sponsor_pool = game
+        .sponsor_pool
+        .checked_sub(amount)
+        .ok_or(GameError::ArithmeticUnderflow)?;
+    game.total_prize_pool = game
+        .total_prize_pool
+        .checked_sub(amount)
+        .ok_or(GameError::ArithmeticUnderflow)?;
+    game.sponsor_count = game
+        .sponsor_count
+        .checked_sub(1)
+        .ok_or(GameError::ArithmeticUnderflow)?;
+
+    emit!(SponsorRefunded {
+        game: game.key(),
+        sponsor: ctx.accounts.sponsor.key(),
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "Refunded {} to sponsor {}, {} sponsors left",
+        amount,
+        ctx.accounts.sponsor.key(),
+        game.sponsor_count
+    );
+
+    Ok(())
+}
@@ -2,12 +2,7 @@ use crate::constants::*;
 use crate::error::GameError;
 use crate::state::*;
 use anchor_lang::prelude::*;
-use anchor_spl::{
-    associated_token::AssociatedToken,
-    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
-};
-use ephemeral_rollups_sdk::anchor::{commit, delegate};
-use ephemeral_rollups_sdk::cpi::DelegateConfig;
+use ephemeral_rollups_sdk::anchor::commit;
 use ephemeral_rollups_sdk::ephem::commit_and_undelegate_accounts;
 
 #[commit]
@@ -78,7 +73,7 @@ pub fn undelegate_game_handler<'c: 'info, 'info>(
 }
 
 #[derive(Accounts)]
-pub struct CloseGame<'info> {
+pub struct ForceCloseGame<'info> {
     #[account(
         mut,
         seeds = [b"game", game.config_id.as_ref(), &game.game_id.to_le_bytes().as_ref()],
@@ -94,8 +89,8 @@ pub struct CloseGame<'info> {
     pub authority: Signer<'info>,
 }
 
-pub fn close_game_handler<'c: 'info, 'info>(
-    ctx: Context<'_, '_, 'c, 'info, CloseGame<'info>>,
+pub fn force_close_game_handler<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, ForceCloseGame<'info>>,
 ) -> Result<()> {
     {
         msg!("Start close_game_handler");
@@ -159,12 +154,17 @@ pub fn reset_game_handler<'c: 'info, 'info>(
         // Change game status to in progress
         game.game_status = GameStatus::InProgress;
         game.current_turn = 0; // First player starts
-        game.houses_remaining = TOTAL_HOUSES; // First player starts
-        game.hotels_remaining = TOTAL_HOTELS; // First player starts
+        game.randomness_slot = clock.slot;
+        game.houses_remaining = game.rules.total_houses; // First player starts
+        game.hotels_remaining = game.rules.total_hotels; // First player starts
         game.bank_balance = 1_000_000; // First player starts
         game.winner = None; // First player starts
         game.active_trades = vec![]; // First player starts
         game.next_trade_id = 0; // First player starts
+        game.active_auction = None;
+        game.festival = None;
+        game.chance_deck = DeckState::new(game.chance_deck.cards.clone());
+        game.community_chest_deck = DeckState::new(game.community_chest_deck.cards.clone());
         game.turn_started_at = clock.unix_timestamp;
 
         // Reset all properties to unowned state
@@ -192,15 +192,15 @@ pub fn reset_game_handler<'c: 'info, 'info>(
 
             let mut player_account = Account::<PlayerState>::try_from(data_account_info)?;
 
-            player_account.cash_balance = STARTING_MONEY as u64;
+            player_account.cash_balance = game.rules.starting_money;
             player_account.position = 0;
             player_account.in_jail = false;
             player_account.jail_turns = 0;
             player_account.doubles_count = 0;
             player_account.is_bankrupt = false;
             player_account.properties_owned = Vec::new();
-            player_account.get_out_of_jail_cards = 0;
-            player_account.net_worth = STARTING_MONEY as u64;
+            player_account.get_out_of_jail_cards = Vec::new();
+            player_account.net_worth = game.rules.starting_money;
             player_account.last_rent_collected = clock.unix_timestamp;
             player_account.festival_boost_turns = 0;
             player_account.has_rolled_dice = false;
@@ -209,10 +209,11 @@ pub fn reset_game_handler<'c: 'info, 'info>(
             player_account.pending_property_position = None;
             player_account.needs_chance_card = false;
             player_account.needs_community_chest_card = false;
-            player_account.needs_bankruptcy_check = false;
+            player_account.clear_debt();
             player_account.needs_special_space_action = false;
             player_account.pending_special_space_position = None;
             player_account.card_drawn_at = None;
+            player_account.double_rent_pending = false;
 
             player_account.exit(&crate::ID)?;
 
@@ -35,13 +35,16 @@ pub struct CreateTrade<'info> {
     pub clock: Sysvar<'info, Clock>,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn create_trade_handler(
     ctx: Context<CreateTrade>,
     trade_type: TradeType,
     proposer_money: u64,
     receiver_money: u64,
-    proposer_property: Option<u8>,
-    receiver_property: Option<u8>,
+    proposer_properties: Vec<u8>,
+    receiver_properties: Vec<u8>,
+    proposer_jail_cards: u8,
+    receiver_jail_cards: u8,
 ) -> Result<()> {
     let game = &mut ctx.accounts.game;
     let proposer_state = &mut ctx.accounts.proposer_state;
@@ -54,130 +57,218 @@ pub fn create_trade_handler(
     // Check if we can add a new trade
     require!(game.can_add_trade(), GameError::TooManyActiveTrades);
 
-    // Validate trade parameters
-    require!(
-        proposer_money <= proposer_state.cash_balance,
-        GameError::InsufficientFunds
-    );
-
     proposer_state.record_action(clock);
 
-    // Validate proposer property ownership
-    if let Some(prop_pos) = proposer_property {
-        require!(
-            proposer_state.properties_owned.contains(&prop_pos),
-            GameError::PropertyNotOwned
-        );
+    // Create the trade
+    let trade_id = game.get_next_trade_id();
+    let expires_at = clock.unix_timestamp + game.rules.trade_expiry_seconds;
+    let trade = TradeInfo {
+        id: trade_id,
+        proposer: ctx.accounts.proposer.key(),
+        receiver: ctx.accounts.receiver.key(),
+        trade_type: trade_type.clone(),
+        proposer_money,
+        receiver_money,
+        proposer_properties: proposer_properties.clone(),
+        receiver_properties: receiver_properties.clone(),
+        proposer_jail_cards,
+        receiver_jail_cards,
+        parent_trade_id: None,
+        round: 0,
+        status: TradeStatus::Pending,
+        created_at: clock.unix_timestamp,
+        expires_at,
+    };
 
-        // Also validate ownership in GameState properties
-        let property = game.get_property(prop_pos)?;
-        require!(
-            property.owner == Some(ctx.accounts.proposer.key()),
-            GameError::PropertyNotOwnedByPlayer
-        );
+    validate_trade_offer(game, &trade, proposer_state, receiver_state)?;
 
-        // Cannot trade mortgaged properties
-        require!(
-            !property.is_mortgaged,
-            GameError::CannotTradeMortgagedProperties
-        );
-    }
+    game.active_trades.push(trade);
 
-    // Validate receiver property ownership
-    if let Some(prop_pos) = receiver_property {
-        require!(
-            receiver_state.properties_owned.contains(&prop_pos),
-            GameError::PropertyNotOwned
-        );
+    emit!(TradeCreated {
+        game: game.key(),
+        trade_id,
+        proposer: ctx.accounts.proposer.key(),
+        receiver: ctx.accounts.receiver.key(),
+        trade_type,
+        proposer_money,
+        receiver_money,
+        proposer_properties,
+        receiver_properties,
+        proposer_jail_cards,
+        receiver_jail_cards,
+        expires_at,
+    });
 
-        // Also validate ownership in GameState properties
-        let property = game.get_property(prop_pos)?;
-        require!(
-            property.owner == Some(ctx.accounts.receiver.key()),
-            GameError::PropertyNotOwnedByPlayer
-        );
+    Ok(())
+}
 
-        // Cannot trade mortgaged properties
-        require!(
-            !property.is_mortgaged,
-            GameError::CannotTradeMortgagedProperties
-        );
-    }
+/// Validates the terms of a new or countered offer against both players.
+fn validate_trade_offer(
+    game: &GameState,
+    trade: &TradeInfo,
+    proposer_state: &PlayerState,
+    receiver_state: &PlayerState,
+) -> Result<()> {
+    require!(
+        trade.proposer != trade.receiver,
+        GameError::CannotTradeWithSelf
+    );
+
+    // Validate trade parameters
+    require!(
+        trade.proposer_money <= proposer_state.cash_balance,
+        GameError::InsufficientFunds
+    );
+    require!(
+        trade.proposer_jail_cards <= proposer_state.jail_card_count(),
+        GameError::NoGetOutOfJailCards
+    );
+    require!(
+        trade.receiver_jail_cards <= receiver_state.jail_card_count(),
+        GameError::NoGetOutOfJailCards
+    );
+
+    // Validate property ownership on both sides
+    validate_trade_properties(
+        game,
+        proposer_state,
+        &trade.proposer,
+        &trade.proposer_properties,
+    )?;
+    validate_trade_properties(
+        game,
+        receiver_state,
+        &trade.receiver,
+        &trade.receiver_properties,
+    )?;
+
+    let has_proposer_properties = !trade.proposer_properties.is_empty();
+    let has_receiver_properties = !trade.receiver_properties.is_empty();
+    let has_jail_cards = trade.proposer_jail_cards > 0 || trade.receiver_jail_cards > 0;
 
     // Validate trade type matches the provided parameters
-    match trade_type {
+    match trade.trade_type {
         TradeType::MoneyOnly => {
             require!(
-                proposer_property.is_none() && receiver_property.is_none(),
+                !has_proposer_properties && !has_receiver_properties,
                 GameError::InvalidTradeType
             );
             require!(
-                proposer_money > 0 || receiver_money > 0,
+                trade.proposer_money > 0 || trade.receiver_money > 0,
                 GameError::InvalidTradeType
             );
+            require!(!has_jail_cards, GameError::InvalidTradeType);
         }
         TradeType::PropertyOnly => {
             require!(
-                proposer_money == 0 && receiver_money == 0,
+                trade.proposer_money == 0 && trade.receiver_money == 0,
                 GameError::InvalidTradeType
             );
             require!(
-                proposer_property.is_some() || receiver_property.is_some(),
+                has_proposer_properties || has_receiver_properties,
                 GameError::InvalidTradeType
             );
+            require!(!has_jail_cards, GameError::InvalidTradeType);
         }
         TradeType::MoneyForProperty => {
             require!(
-                proposer_money > 0 && receiver_property.is_some(),
+                trade.proposer_money > 0 && has_receiver_properties,
                 GameError::InvalidTradeType
             );
             require!(
-                receiver_money == 0 && proposer_property.is_none(),
+                trade.receiver_money == 0 && !has_proposer_properties,
                 GameError::InvalidTradeType
             );
+            require!(!has_jail_cards, GameError::InvalidTradeType);
         }
         TradeType::PropertyForMoney => {
             require!(
-                proposer_property.is_some() && receiver_money > 0,
+                has_proposer_properties && trade.receiver_money > 0,
+                GameError::InvalidTradeType
+            );
+            require!(
+                trade.proposer_money == 0 && !has_receiver_properties,
+                GameError::InvalidTradeType
+            );
+            require!(!has_jail_cards, GameError::InvalidTradeType);
+        }
+        TradeType::Bundle => {
+            // Each side has to put something on the table
+            require!(
+                trade.proposer_money > 0
+                    || has_proposer_properties
+                    || trade.proposer_jail_cards > 0,
                 GameError::InvalidTradeType
             );
             require!(
-                proposer_money == 0 && receiver_property.is_none(),
+                trade.receiver_money > 0
+                    || has_receiver_properties
+                    || trade.receiver_jail_cards > 0,
                 GameError::InvalidTradeType
             );
         }
     }
 
-    // Create the trade
-    let trade_id = game.get_next_trade_id();
-    let trade = TradeInfo {
-        id: trade_id,
-        proposer: ctx.accounts.proposer.key(),
-        receiver: ctx.accounts.receiver.key(),
-        trade_type: trade_type.clone(),
-        proposer_money,
-        receiver_money,
-        proposer_property,
-        receiver_property,
-        status: TradeStatus::Pending,
-        created_at: clock.unix_timestamp,
-        expires_at: clock.unix_timestamp + TRADE_EXPIRY_SECONDS,
-    };
+    Ok(())
+}
 
-    game.active_trades.push(trade);
+/// Checks that every listed property belongs to `owner` and can be traded.
+fn validate_trade_properties(
+    game: &GameState,
+    owner_state: &PlayerState,
+    owner: &Pubkey,
+    positions: &[u8],
+) -> Result<()> {
+    require!(
+        positions.len() <= MAX_PROPERTIES_IN_TRADE,
+        GameError::TooManyPropertiesInTrade
+    );
 
-    emit!(TradeCreated {
-        game: game.key(),
-        trade_id,
-        proposer: ctx.accounts.proposer.key(),
-        receiver: ctx.accounts.receiver.key(),
-        trade_type,
-        proposer_money,
-        receiver_money,
-        proposer_property,
-        receiver_property,
-        expires_at: clock.unix_timestamp + TRADE_EXPIRY_SECONDS,
-    });
+    for (i, prop_pos) in positions.iter().enumerate() {
+        require!(
+            !positions[..i].contains(prop_pos),
+            GameError::DuplicatePropertyInTrade
+        );
+
+        require!(
+            owner_state.properties_owned.contains(prop_pos),
+            GameError::PropertyNotOwned
+        );
+
+        // Also validate ownership in GameState properties
+        let property = game.get_property(*prop_pos)?;
+        require!(
+            property.owner == Some(*owner),
+            GameError::PropertyNotOwnedByPlayer
+        );
+
+        // Cannot trade mortgaged properties
+        require!(
+            !property.is_mortgaged,
+            GameError::CannotTradeMortgagedProperties
+        );
+    }
+
+    Ok(())
+}
+
+/// Moves the listed properties from one player to another. Callers must
+/// validate the list with `validate_trade_properties` first.
+fn transfer_trade_properties(
+    game: &mut GameState,
+    from_state: &mut PlayerState,
+    to_state: &mut PlayerState,
+    positions: &[u8],
+) -> Result<()> {
+    for &prop_pos in positions {
+        // Update PlayerState ownership arrays
+        from_state.properties_owned.retain(|&x| x != prop_pos);
+        to_state.properties_owned.push(prop_pos);
+
+        // Update GameState property ownership
+        let property = game.get_property_mut(prop_pos)?;
+        property.owner = Some(to_state.wallet);
+    }
 
     Ok(())
 }
@@ -236,6 +327,10 @@ pub fn accept_trade_handler(ctx: Context<AcceptTrade>, trade_id: u8) -> Result<(
         trade.receiver == ctx.accounts.accepter.key(),
         GameError::NotTradeTarget
     );
+    require!(
+        trade.proposer == proposer_state.wallet,
+        GameError::InvalidPlayerAccount
+    );
     require!(
         trade.expires_at > clock.unix_timestamp,
         GameError::TradeExpired
@@ -251,76 +346,78 @@ pub fn accept_trade_handler(ctx: Context<AcceptTrade>, trade_id: u8) -> Result<(
         GameError::InsufficientFunds
     );
 
-    accepter_state.record_action(clock);
-
-    if let Some(prop_pos) = trade.proposer_property {
-        require!(
-            proposer_state.properties_owned.contains(&prop_pos),
-            GameError::PropertyNotOwned
-        );
-
-        // Validate ownership in GameState properties
-        let property = game.get_property(prop_pos)?;
-        require!(
-            property.owner == Some(trade.proposer),
-            GameError::PropertyNotOwnedByPlayer
-        );
-        require!(
-            !property.is_mortgaged,
-            GameError::CannotTradeMortgagedProperties
-        );
-    }
+    require!(
+        proposer_state.jail_card_count() >= trade.proposer_jail_cards,
+        GameError::NoGetOutOfJailCards
+    );
+    require!(
+        accepter_state.jail_card_count() >= trade.receiver_jail_cards,
+        GameError::NoGetOutOfJailCards
+    );
 
-    if let Some(prop_pos) = trade.receiver_property {
-        require!(
-            accepter_state.properties_owned.contains(&prop_pos),
-            GameError::PropertyNotOwned
-        );
+    accepter_state.record_action(clock);
 
-        // Validate ownership in GameState properties
-        let property = game.get_property(prop_pos)?;
-        require!(
-            property.owner == Some(trade.receiver),
-            GameError::PropertyNotOwnedByPlayer
-        );
-        require!(
-            !property.is_mortgaged,
-            GameError::CannotTradeMortgagedProperties
-        );
-    }
+    // Re-check every listed property before anything moves
+    validate_trade_properties(
+        game,
+        proposer_state,
+        &trade.proposer,
+        &trade.proposer_properties,
+    )?;
+    validate_trade_properties(
+        game,
+        accepter_state,
+        &trade.receiver,
+        &trade.receiver_properties,
+    )?;
 
     // Execute the trade
     // Transfer money
     if trade.proposer_money > 0 {
-        proposer_state.cash_balance -= trade.proposer_money;
-        accepter_state.cash_balance += trade.proposer_money;
+        proposer_state.cash_balance = proposer_state
+            .cash_balance
+            .checked_sub(trade.proposer_money)
+            .ok_or(GameError::ArithmeticUnderflow)?;
+        accepter_state.cash_balance = accepter_state
+            .cash_balance
+            .checked_add(trade.proposer_money)
+            .ok_or(GameError::ArithmeticOverflow)?;
     }
 
     if trade.receiver_money > 0 {
-        accepter_state.cash_balance -= trade.receiver_money;
-        proposer_state.cash_balance += trade.receiver_money;
+        accepter_state.cash_balance = accepter_state
+            .cash_balance
+            .checked_sub(trade.receiver_money)
+            .ok_or(GameError::ArithmeticUnderflow)?;
+        proposer_state.cash_balance = proposer_state
+            .cash_balance
+            .checked_add(trade.receiver_money)
+            .ok_or(GameError::ArithmeticOverflow)?;
     }
 
     // Transfer properties
-    if let Some(prop_pos) = trade.proposer_property {
-        // Update PlayerState ownership arrays
-        proposer_state.properties_owned.retain(|&x| x != prop_pos);
-        accepter_state.properties_owned.push(prop_pos);
-
-        // Update GameState property ownership
-        let property = game.get_property_mut(prop_pos)?;
-        property.owner = Some(trade.receiver);
-    }
-
-    if let Some(prop_pos) = trade.receiver_property {
-        // Update PlayerState ownership arrays
-        accepter_state.properties_owned.retain(|&x| x != prop_pos);
-        proposer_state.properties_owned.push(prop_pos);
-
-        // Update GameState property ownership
-        let property = game.get_property_mut(prop_pos)?;
-        property.owner = Some(trade.proposer);
-    }
+    transfer_trade_properties(
+        game,
+        proposer_state,
+        accepter_state,
+        &trade.proposer_properties,
+    )?;
+    transfer_trade_properties(
+        game,
+        accepter_state,
+        proposer_state,
+        &trade.receiver_properties,
+    )?;
+
+    // Transfer jail cards
+    let mut proposer_cards = proposer_state.take_jail_cards(trade.proposer_jail_cards)?;
+    let mut receiver_cards = accepter_state.take_jail_cards(trade.receiver_jail_cards)?;
+    accepter_state
+        .get_out_of_jail_cards
+        .append(&mut proposer_cards);
+    proposer_state
+        .get_out_of_jail_cards
+        .append(&mut receiver_cards);
 
     // Update trade status and remove from active trades
     game.remove_trade_by_id(trade_id);
@@ -336,6 +433,127 @@ pub fn accept_trade_handler(ctx: Context<AcceptTrade>, trade_id: u8) -> Result<(
     Ok(())
 }
 
+#[derive(Accounts)]
+pub struct CounterTrade<'info> {
+    #[account(
+        mut,
+        seeds = [b"game", game.config_id.as_ref(), &game.game_id.to_le_bytes().as_ref()],
+        bump = game.bump,
+        constraint = game.game_status == GameStatus::InProgress @ GameError::GameNotInProgress
+    )]
+    pub game: Box<Account<'info, GameState>>,
+
+    // Receiver of the original trade, proposer of the counter-offer
+    #[account(
+        mut,
+        seeds = [b"player", game.key().as_ref(), counterer.key().as_ref()],
+        bump
+    )]
+    pub counterer_state: Box<Account<'info, PlayerState>>,
+
+    // Proposer of the original trade, receiver of the counter-offer
+    #[account(
+        seeds = [b"player", game.key().as_ref(), original_proposer_state.wallet.as_ref()],
+        bump
+    )]
+    pub original_proposer_state: Box<Account<'info, PlayerState>>,
+
+    #[account(mut)]
+    pub counterer: Signer<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn counter_trade_handler(
+    ctx: Context<CounterTrade>,
+    trade_id: u8,
+    trade_type: TradeType,
+    proposer_money: u64,
+    receiver_money: u64,
+    proposer_properties: Vec<u8>,
+    receiver_properties: Vec<u8>,
+    proposer_jail_cards: u8,
+    receiver_jail_cards: u8,
+) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let counterer_state = &mut ctx.accounts.counterer_state;
+    let original_proposer_state = &ctx.accounts.original_proposer_state;
+    let counterer = ctx.accounts.counterer.key();
+    let clock = &ctx.accounts.clock;
+
+    // Clean up expired trades first
+    game.cleanup_expired_trades(clock.unix_timestamp);
+
+    // Find the trade being countered
+    let parent = game
+        .find_trade_by_id(trade_id)
+        .ok_or(GameError::TradeNotFound)?
+        .clone();
+
+    require!(
+        parent.status == TradeStatus::Pending,
+        GameError::TradeNotPending
+    );
+    require!(parent.receiver == counterer, GameError::NotTradeTarget);
+    require!(
+        parent.proposer == original_proposer_state.wallet,
+        GameError::InvalidPlayerAccount
+    );
+
+    counterer_state.record_action(clock);
+
+    // The counter-offer flips the roles of the two players
+    let new_trade_id = game.get_next_trade_id();
+    let round = parent
+        .round
+        .checked_add(1)
+        .ok_or(GameError::ArithmeticOverflow)?;
+    let expires_at = clock.unix_timestamp + game.rules.trade_expiry_seconds;
+    let trade = TradeInfo {
+        id: new_trade_id,
+        proposer: counterer,
+        receiver: parent.proposer,
+        trade_type: trade_type.clone(),
+        proposer_money,
+        receiver_money,
+        proposer_properties: proposer_properties.clone(),
+        receiver_properties: receiver_properties.clone(),
+        proposer_jail_cards,
+        receiver_jail_cards,
+        parent_trade_id: Some(parent.id),
+        round,
+        status: TradeStatus::Pending,
+        created_at: clock.unix_timestamp,
+        expires_at,
+    };
+
+    validate_trade_offer(game, &trade, counterer_state, original_proposer_state)?;
+
+    // Replace the pending trade with the counter-offer
+    game.remove_trade_by_id(trade_id);
+    game.active_trades.push(trade);
+
+    emit!(TradeCountered {
+        game: game.key(),
+        trade_id: new_trade_id,
+        parent_trade_id: parent.id,
+        round,
+        proposer: counterer,
+        receiver: parent.proposer,
+        trade_type,
+        proposer_money,
+        receiver_money,
+        proposer_properties,
+        receiver_properties,
+        proposer_jail_cards,
+        receiver_jail_cards,
+        expires_at,
+    });
+
+    Ok(())
+}
+
 #[derive(Accounts)]
 pub struct RejectTrade<'info> {
     #[account(
@@ -359,7 +577,7 @@ pub struct RejectTrade<'info> {
 }
 
 pub fn reject_trade_handler(ctx: Context<RejectTrade>, trade_id: u8) -> Result<()> {
-    let game_key = ctx.accounts.game.key().clone();
+    let game_key = ctx.accounts.game.key();
     let game = &mut ctx.accounts.game;
     let clock = &ctx.accounts.clock;
 
@@ -424,7 +642,7 @@ pub struct CancelTrade<'info> {
 }
 
 pub fn cancel_trade_handler(ctx: Context<CancelTrade>, trade_id: u8) -> Result<()> {
-    let game_key = ctx.accounts.game.key().clone();
+    let game_key = ctx.accounts.game.key();
     let game = &mut ctx.accounts.game;
     let clock = &ctx.accounts.clock;
 
@@ -1,4 +1,7 @@
 #![allow(unexpected_cfgs)]
+// Anchor's #[program] expands IDL handlers at the crate root that still call
+// the deprecated AccountInfo::realloc, an item level allow can't reach them
+#![allow(deprecated)]
 pub mod constants;
 pub mod error;
 pub mod instructions;
@@ -9,6 +12,8 @@ use anchor_lang::prelude::*;
 use ephemeral_rollups_sdk::anchor::ephemeral;
 
 pub use constants::*;
+// A few handlers share their names with the #[program] re-exports
+#[allow(ambiguous_glob_reexports)]
 pub use instructions::*;
 pub use state::*;
 pub use utils::*;
@@ -39,17 +44,87 @@ pub mod panda_monopoly {
         ctx: Context<UpdatePlatformConfig>,
         fee_basis_points: Option<u16>,
         fee_vault: Option<Pubkey>,
+        rules_limits: Option<GameRulesLimits>,
+        delegation: Option<DelegationSettings>,
     ) -> Result<()> {
-        instructions::platform::update_platform_config_handler(ctx, fee_basis_points, fee_vault)
+        instructions::platform::update_platform_config_handler(
+            ctx,
+            fee_basis_points,
+            fee_vault,
+            rules_limits,
+            delegation,
+        )
+    }
+
+    pub fn propose_platform_authority(
+        ctx: Context<UpdatePlatformConfig>,
+        new_authority: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::platform::propose_platform_authority_handler(ctx, new_authority)
+    }
+
+    pub fn accept_platform_authority(ctx: Context<AcceptPlatformAuthority>) -> Result<()> {
+        instructions::platform::accept_platform_authority_handler(ctx)
+    }
+
+    pub fn set_platform_paused(ctx: Context<UpdatePlatformConfig>, paused: bool) -> Result<()> {
+        instructions::platform::set_platform_paused_handler(ctx, paused)
+    }
+
+    pub fn add_allowed_mint(
+        ctx: Context<AddAllowedMint>,
+        mint: Pubkey,
+        min_entry_fee: u64,
+        max_entry_fee: u64,
+        fee_basis_points_override: Option<u16>,
+    ) -> Result<()> {
+        instructions::platform::add_allowed_mint_handler(
+            ctx,
+            mint,
+            min_entry_fee,
+            max_entry_fee,
+            fee_basis_points_override,
+        )
+    }
+
+    pub fn remove_allowed_mint(ctx: Context<RemoveAllowedMint>) -> Result<()> {
+        instructions::platform::remove_allowed_mint_handler(ctx)
+    }
+
+    pub fn initialize_card_deck(ctx: Context<InitializeCardDeck>, kind: DeckKind) -> Result<()> {
+        instructions::platform::initialize_card_deck_handler(ctx, kind)
+    }
+
+    pub fn update_card_deck(
+        ctx: Context<UpdateCardDeck>,
+        kind: DeckKind,
+        cards: Vec<CardData>,
+    ) -> Result<()> {
+        instructions::platform::update_card_deck_handler(ctx, kind, cards)
     }
 
     // Game management instructions
+    #[allow(clippy::too_many_arguments)]
     pub fn initialize_game(
         ctx: Context<InitializeGame>,
         entry_fee: u64,
         time_limit_seconds: Option<i64>,
+        rules: Option<GameRules>,
+        max_players: u8,
+        payout_bps: Option<Vec<u16>>,
+        delegation: Option<DelegationSettings>,
+        use_vrf: bool,
     ) -> Result<()> {
-        instructions::initialize::initialize_game_handler(ctx, entry_fee, time_limit_seconds)
+        instructions::initialize::initialize_game_handler(
+            ctx,
+            entry_fee,
+            time_limit_seconds,
+            rules,
+            max_players,
+            payout_bps,
+            delegation,
+            use_vrf,
+        )
     }
 
     pub fn cancel_game<'c: 'info, 'info>(
@@ -66,12 +141,26 @@ pub mod panda_monopoly {
         instructions::leave_game::leave_game_handler(ctx)
     }
 
+    pub fn sponsor_game(ctx: Context<SponsorGame>, amount: u64) -> Result<()> {
+        instructions::sponsor::sponsor_game_handler(ctx, amount)
+    }
+
+    pub fn refund_sponsor(ctx: Context<RefundSponsor>) -> Result<()> {
+        instructions::sponsor::refund_sponsor_handler(ctx)
+    }
+
     pub fn start_game<'c: 'info, 'info>(
         ctx: Context<'_, '_, 'c, 'info, StartGame<'info>>,
     ) -> Result<()> {
         instructions::initialize::start_game_handler(ctx)
     }
 
+    pub fn undelegate_stalled_start<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, UndelegateStalledStart<'info>>,
+    ) -> Result<()> {
+        instructions::initialize::undelegate_stalled_start_handler(ctx)
+    }
+
     // Game ending instruction
     pub fn end_game<'c: 'info, 'info>(
         ctx: Context<'_, '_, 'c, 'info, EndGame<'info>>,
@@ -85,8 +174,21 @@ pub mod panda_monopoly {
         use_vrf: bool,
         client_seed: u8,
         dice_roll: Option<[u8; 2]>,
+        reveal: Option<[u8; 32]>,
     ) -> Result<()> {
-        instructions::dice::roll_dice_handler(ctx, use_vrf, client_seed, dice_roll)
+        instructions::dice::roll_dice_handler(ctx, use_vrf, client_seed, dice_roll, reveal)
+    }
+
+    pub fn commit_randomness(
+        ctx: Context<CommitRandomness>,
+        commitment: [u8; 32],
+        reveal: Option<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::dice::commit_randomness_handler(ctx, commitment, reveal)
+    }
+
+    pub fn refresh_randomness_slot(ctx: Context<RefreshRandomnessSlot>) -> Result<()> {
+        instructions::dice::refresh_randomness_slot_handler(ctx)
     }
 
     pub fn callback_roll_dice(
@@ -96,15 +198,15 @@ pub mod panda_monopoly {
         instructions::dice::callback_roll_dice(ctx, randomness)
     }
 
-    pub fn callback_draw_chance_card(
-        ctx: Context<CallbackDrawChanceCardCtx>,
+    pub fn callback_draw_chance_card<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, CallbackDrawChanceCardCtx<'info>>,
         randomness: [u8; 32],
     ) -> Result<()> {
         instructions::special_spaces::callback_draw_chance_card(ctx, randomness)
     }
 
-    pub fn callback_draw_community_chest_card(
-        ctx: Context<CallbackDrawCommunityChestCardCtx>,
+    pub fn callback_draw_community_chest_card<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, CallbackDrawCommunityChestCardCtx<'info>>,
         randomness: [u8; 32],
     ) -> Result<()> {
         instructions::special_spaces::callback_draw_community_chest_card(ctx, randomness)
@@ -128,6 +230,10 @@ pub mod panda_monopoly {
         instructions::bankruptcy::declare_bankruptcy_handler(ctx)
     }
 
+    pub fn settle_debt(ctx: Context<SettleDebt>) -> Result<()> {
+        instructions::bankruptcy::settle_debt_handler(ctx)
+    }
+
     // Tax instructions
     pub fn pay_mev_tax_handler(ctx: Context<PayTax>) -> Result<()> {
         instructions::special_spaces::pay_mev_tax_handler(ctx)
@@ -137,31 +243,39 @@ pub mod panda_monopoly {
         instructions::special_spaces::pay_priority_fee_tax_handler(ctx)
     }
 
-    pub fn draw_chance_card(
-        ctx: Context<DrawChanceCard>,
+    pub fn host_festival(ctx: Context<HostFestival>, property_position: u8) -> Result<()> {
+        instructions::special_spaces::host_festival_handler(ctx, property_position)
+    }
+
+    pub fn draw_chance_card<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, DrawChanceCard<'info>>,
         use_vrf: bool,
         client_seed: u8,
         card_index: Option<u8>,
+        reveal: Option<[u8; 32]>,
     ) -> Result<()> {
         instructions::special_spaces::draw_chance_card_handler(
             ctx,
             use_vrf,
             client_seed,
             card_index,
+            reveal,
         )
     }
 
-    pub fn draw_community_chest_card(
-        ctx: Context<DrawCommunityChestCard>,
+    pub fn draw_community_chest_card<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, DrawCommunityChestCard<'info>>,
         use_vrf: bool,
         client_seed: u8,
         card_index: Option<u8>,
+        reveal: Option<[u8; 32]>,
     ) -> Result<()> {
         instructions::special_spaces::draw_community_chest_card_handler(
             ctx,
             use_vrf,
             client_seed,
             card_index,
+            reveal,
         )
     }
 
@@ -181,6 +295,19 @@ pub mod panda_monopoly {
         instructions::property::decline_property_v2_handler(ctx, position)
     }
 
+    // Auction instructions
+    pub fn start_auction(ctx: Context<StartAuction>, position: u8) -> Result<()> {
+        instructions::auction::start_auction_handler(ctx, position)
+    }
+
+    pub fn place_bid(ctx: Context<PlaceBid>, bid_amount: u64) -> Result<()> {
+        instructions::auction::place_bid_handler(ctx, bid_amount)
+    }
+
+    pub fn settle_auction(ctx: Context<SettleAuction>) -> Result<()> {
+        instructions::auction::settle_auction_handler(ctx)
+    }
+
     pub fn pay_rent_v2(ctx: Context<PayRentV2>, position: u8) -> Result<()> {
         instructions::property::pay_rent_v2_handler(ctx, position)
     }
@@ -202,21 +329,26 @@ pub mod panda_monopoly {
     }
 
     // Trading instructions
+    #[allow(clippy::too_many_arguments)]
     pub fn create_trade(
         ctx: Context<CreateTrade>,
         trade_type: TradeType,
         proposer_money: u64,
         receiver_money: u64,
-        proposer_property: Option<u8>,
-        receiver_property: Option<u8>,
+        proposer_properties: Vec<u8>,
+        receiver_properties: Vec<u8>,
+        proposer_jail_cards: u8,
+        receiver_jail_cards: u8,
     ) -> Result<()> {
         instructions::trading::create_trade_handler(
             ctx,
             trade_type,
             proposer_money,
             receiver_money,
-            proposer_property,
-            receiver_property,
+            proposer_properties,
+            receiver_properties,
+            proposer_jail_cards,
+            receiver_jail_cards,
         )
     }
 
@@ -224,6 +356,31 @@ pub mod panda_monopoly {
         instructions::trading::accept_trade_handler(ctx, trade_id)
     }
 
+    #[allow(clippy::too_many_arguments)]
+    pub fn counter_trade(
+        ctx: Context<CounterTrade>,
+        trade_id: u8,
+        trade_type: TradeType,
+        proposer_money: u64,
+        receiver_money: u64,
+        proposer_properties: Vec<u8>,
+        receiver_properties: Vec<u8>,
+        proposer_jail_cards: u8,
+        receiver_jail_cards: u8,
+    ) -> Result<()> {
+        instructions::trading::counter_trade_handler(
+            ctx,
+            trade_id,
+            trade_type,
+            proposer_money,
+            receiver_money,
+            proposer_properties,
+            receiver_properties,
+            proposer_jail_cards,
+            receiver_jail_cards,
+        )
+    }
+
     pub fn reject_trade(ctx: Context<RejectTrade>, trade_id: u8) -> Result<()> {
         instructions::trading::reject_trade_handler(ctx, trade_id)
     }
@@ -250,23 +407,44 @@ pub mod panda_monopoly {
         instructions::permissionless::force_bankruptcy_for_timeout_handler(ctx)
     }
 
-    // for test
+    pub fn close_player_state(ctx: Context<ClosePlayerState>) -> Result<()> {
+        instructions::close_game::close_player_state_handler(ctx)
+    }
+
+    pub fn close_sponsorship(ctx: Context<CloseSponsorship>) -> Result<()> {
+        instructions::close_game::close_sponsorship_handler(ctx)
+    }
+
+    pub fn close_game(ctx: Context<CloseGame>) -> Result<()> {
+        instructions::close_game::close_game_handler(ctx)
+    }
+
+    pub fn finalize_game<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, FinalizeGame<'info>>,
+    ) -> Result<()> {
+        instructions::end_game::finalize_game_handler(ctx)
+    }
+
+    // for test, only compiled into `local` builds
 
+    #[cfg(feature = "local")]
     pub fn reset_game_handler<'c: 'info, 'info>(
         ctx: Context<'_, '_, 'c, 'info, ResetGame<'info>>,
     ) -> Result<()> {
         instructions::test::reset_game_handler(ctx)
     }
 
+    #[cfg(feature = "local")]
     pub fn undelegate_game_handler<'c: 'info, 'info>(
         ctx: Context<'_, '_, 'c, 'info, UndelegateGame<'info>>,
     ) -> Result<()> {
         instructions::test::undelegate_game_handler(ctx)
     }
 
+    #[cfg(feature = "local")]
     pub fn close_game_handler<'c: 'info, 'info>(
-        ctx: Context<'_, '_, 'c, 'info, CloseGame<'info>>,
+        ctx: Context<'_, '_, 'c, 'info, ForceCloseGame<'info>>,
     ) -> Result<()> {
-        instructions::test::close_game_handler(ctx)
+        instructions::test::force_close_game_handler(ctx)
     }
 }
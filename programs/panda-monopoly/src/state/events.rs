@@ -1,4 +1,7 @@
-use crate::{state::TradeType, GameEndReason};
+use crate::{
+    state::{DeckKind, TradeType},
+    GameEndReason,
+};
 use anchor_lang::prelude::*;
 
 #[event]
@@ -6,7 +9,7 @@ pub struct ChanceCardDrawn {
     pub player: Pubkey,
     pub game: Pubkey,
     pub card_index: u8,
-    pub effect_type: u8, // 0=Money, 1=Move, 2=GoToJail, 3=GetOutOfJailFree, 4=PayPerProperty, 5=CollectFromPlayers
+    pub effect_type: u8, // 0=Money, 1=Move, 2=GoToJail, 3=GetOutOfJailFree, 4=PayPerProperty, 5=CollectFromPlayers, 6=MoveToNearest, 7=RepairFree, 8=PayEachPlayer, 9=MoveToNearestRailroad, 10=MoveToNearestUtility, 11=AdvanceToGo, 12=StreetRepairs
     pub amount: i32,
     pub timestamp: i64,
 }
@@ -16,11 +19,94 @@ pub struct CommunityChestCardDrawn {
     pub player: Pubkey,
     pub game: Pubkey,
     pub card_index: u8,
-    pub effect_type: u8, // 0=Money, 1=Move, 2=GoToJail, 3=GetOutOfJailFree, 4=PayPerProperty, 5=CollectFromPlayers
+    pub effect_type: u8, // 0=Money, 1=Move, 2=GoToJail, 3=GetOutOfJailFree, 4=PayPerProperty, 5=CollectFromPlayers, 6=MoveToNearest, 7=RepairFree, 8=PayEachPlayer, 9=MoveToNearestRailroad, 10=MoveToNearestUtility, 11=AdvanceToGo, 12=StreetRepairs
     pub amount: i32,
     pub timestamp: i64,
 }
 
+#[event]
+pub struct CardDeckUpdated {
+    pub platform: Pubkey,
+    pub kind: DeckKind,
+    pub card_count: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PlatformAuthorityProposed {
+    pub platform: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Option<Pubkey>, // None cancels a pending transfer
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PlatformAuthorityTransferred {
+    pub platform: Pubkey,
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PlatformPauseUpdated {
+    pub platform: Pubkey,
+    pub paused: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AllowedMintAdded {
+    pub platform: Pubkey,
+    pub mint: Pubkey,
+    pub min_entry_fee: u64,
+    pub max_entry_fee: u64,
+    pub fee_basis_points_override: Option<u16>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AllowedMintRemoved {
+    pub platform: Pubkey,
+    pub mint: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FestivalStarted {
+    pub game: Pubkey,
+    pub host: Pubkey,
+    pub property_position: u8,
+    pub rent_multiplier: u64,
+    pub turns: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FestivalEnded {
+    pub game: Pubkey,
+    pub host: Pubkey,
+    pub property_position: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BeachResortBonusPaid {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub properties_owned: u8,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FreeParkingCollected {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct PlayerPassedGo {
     pub game: Pubkey,
@@ -37,6 +123,7 @@ pub struct GameEnded {
     pub winner: Option<Pubkey>,
     pub reason: GameEndReason,
     pub winner_net_worth: Option<u64>,
+    pub ranking: Vec<Pubkey>, // Final placements, winner first
     pub ended_at: i64,
 }
 
@@ -49,8 +136,28 @@ pub struct TradeCreated {
     pub trade_type: TradeType,
     pub proposer_money: u64,
     pub receiver_money: u64,
-    pub proposer_property: Option<u8>,
-    pub receiver_property: Option<u8>,
+    pub proposer_properties: Vec<u8>,
+    pub receiver_properties: Vec<u8>,
+    pub proposer_jail_cards: u8,
+    pub receiver_jail_cards: u8,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct TradeCountered {
+    pub game: Pubkey,
+    pub trade_id: u8,
+    pub parent_trade_id: u8,
+    pub round: u8,
+    pub proposer: Pubkey,
+    pub receiver: Pubkey,
+    pub trade_type: TradeType,
+    pub proposer_money: u64,
+    pub receiver_money: u64,
+    pub proposer_properties: Vec<u8>,
+    pub receiver_properties: Vec<u8>,
+    pub proposer_jail_cards: u8,
+    pub receiver_jail_cards: u8,
     pub expires_at: i64,
 }
 
@@ -106,6 +213,34 @@ pub struct PropertyDeclined {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct AuctionStarted {
+    pub game: Pubkey,
+    pub initiator: Pubkey,
+    pub property_position: u8,
+    pub ends_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuctionBidPlaced {
+    pub game: Pubkey,
+    pub bidder: Pubkey,
+    pub property_position: u8,
+    pub bid_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuctionSettled {
+    pub game: Pubkey,
+    pub property_position: u8,
+    pub winner: Option<Pubkey>,
+    pub winning_bid: u64,
+    pub settled_by: Pubkey,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct RentPaid {
     pub game: Pubkey,
@@ -201,11 +336,31 @@ pub struct TaxPaid {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct GameSponsored {
+    pub game: Pubkey,
+    pub sponsor: Pubkey,
+    pub amount: u64, // Credited to the pool, net of transfer fees
+    pub sponsor_pool: u64,
+    pub total_prize_pool: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SponsorRefunded {
+    pub game: Pubkey,
+    pub sponsor: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct PrizeClaimed {
     pub game: Pubkey,
     pub winner: Pubkey,
-    pub prize_amount: u64,
+    pub placement: u8,     // 0 = first place
+    pub prize_amount: u64, // Paid to the player after the platform fee
+    pub platform_fee: u64,
     pub claimed_at: i64,
 }
 
@@ -218,6 +373,14 @@ pub struct PlayerLeft {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct GameClosed {
+    pub game: Pubkey,
+    pub creator: Pubkey,
+    pub unclaimed_amount: u64, // Swept to the platform fee vault
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct GameCancelled {
     pub game: Pubkey,
@@ -243,6 +406,15 @@ pub struct PlayerBankrupt {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct DebtSettled {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub creditor: Option<Pubkey>, // None when paid to the bank
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct TimeoutPenalty {
     pub game: Pubkey,
@@ -3,7 +3,16 @@ use anchor_lang::prelude::*;
 mod events;
 pub use events::*;
 
-use crate::{error::GameError, get_color_group_properties_enum, get_property_data, STARTING_MONEY};
+use crate::{
+    error::GameError, get_color_group_properties_enum, get_property_data, xorshift64star, CardData,
+    CardEffectType, CHANCE_DECK_SEED, COMMUNITY_CHEST_DECK_SEED, DEFAULT_COMMIT_FREQUENCY_MS,
+    DEFAULT_ER_VALIDATOR, DEFAULT_GRACE_PERIOD_SECONDS, DEFAULT_TURN_TIMEOUT_SECONDS, GO_POSITION,
+    GO_SALARY, JAIL_FINE, MAX_JAIL_CARDS_PER_DECK, MAX_JAIL_TURNS, MEV_TAX, MEV_TAX_POSITION,
+    PRIORITY_FEE_TAX, PRIORITY_FEE_TAX_POSITION, STARTING_MONEY, START_TIMEOUT_SECONDS,
+    TOTAL_BASIS_POINTS, TOTAL_HOTELS, TOTAL_HOUSES, TRADE_EXPIRY_SECONDS,
+};
+use anchor_lang::solana_program::hash::hash;
+use ephemeral_rollups_sdk::cpi::DelegateConfig;
 
 #[account]
 #[derive(InitSpace, Debug)]
@@ -15,19 +24,255 @@ pub struct PlatformConfig {
     pub total_games_created: u64,
     pub next_game_id: u64,
     pub bump: u8,
+    pub rules_limits: GameRulesLimits, // Allowed ranges for per-game rules
+    pub pending_authority: Option<Pubkey>, // Proposed authority, set until accepted
+    pub paused: bool, // Blocks new games, joins and starts; refunds and claims still work
+    pub delegation: DelegationSettings, // Default ER validator and commit cadence for new games
 }
 
 impl PlatformConfig {
-    pub fn calculate_fee(&self, amount: u64) -> u64 {
-        (amount * self.fee_basis_points as u64) / 10000
+    /// Platform cut of `amount`. Games pass the rate snapshotted when they were created.
+    pub fn calculate_fee(amount: u64, fee_basis_points: u16) -> u64 {
+        ((amount as u128 * fee_basis_points as u128) / TOTAL_BASIS_POINTS as u128) as u64
+    }
+}
+
+// House rules chosen by the game creator
+#[derive(Debug, InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct GameRules {
+    pub starting_money: u64,    // Quick-start games raise this
+    pub go_salary: u64,         // Paid for passing GO
+    pub double_go_salary: bool, // Landing exactly on GO pays double
+    pub jail_fine: u64,
+    pub max_jail_turns: u8,
+    pub mev_tax: u64,
+    pub priority_fee_tax: u64,
+    pub no_rent_in_jail: bool,      // Jailed owners don't collect rent
+    pub free_parking_jackpot: bool, // Taxes and fines build a pot paid out on Free Parking
+    pub total_houses: u8,
+    pub total_hotels: u8,
+    pub trade_expiry_seconds: i64,
+    pub turn_timeout_seconds: u64,
+    pub turn_grace_period_seconds: u64,
+}
+
+impl Default for GameRules {
+    fn default() -> Self {
+        Self {
+            starting_money: STARTING_MONEY as u64,
+            go_salary: GO_SALARY as u64,
+            double_go_salary: false,
+            jail_fine: JAIL_FINE as u64,
+            max_jail_turns: MAX_JAIL_TURNS,
+            mev_tax: MEV_TAX as u64,
+            priority_fee_tax: PRIORITY_FEE_TAX as u64,
+            no_rent_in_jail: false,
+            free_parking_jackpot: false,
+            total_houses: TOTAL_HOUSES,
+            total_hotels: TOTAL_HOTELS,
+            trade_expiry_seconds: TRADE_EXPIRY_SECONDS,
+            turn_timeout_seconds: DEFAULT_TURN_TIMEOUT_SECONDS,
+            turn_grace_period_seconds: DEFAULT_GRACE_PERIOD_SECONDS,
+        }
+    }
+}
+
+impl GameRules {
+    /// Salary for passing GO, doubled when landing on it under that variant
+    pub fn go_salary_for(&self, new_position: u8) -> u64 {
+        if self.double_go_salary && new_position == GO_POSITION {
+            self.go_salary.saturating_mul(2)
+        } else {
+            self.go_salary
+        }
+    }
+}
+
+// Ranges the platform allows for GameRules
+#[derive(Debug, InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct GameRulesLimits {
+    pub min_starting_money: u64,
+    pub max_starting_money: u64,
+    pub max_go_salary: u64,
+    pub max_jail_fine: u64,
+    pub max_jail_turns: u8,
+    pub max_tax: u64,
+    pub max_total_houses: u8,
+    pub max_total_hotels: u8,
+    pub min_trade_expiry_seconds: i64,
+    pub max_trade_expiry_seconds: i64,
+    pub min_turn_timeout_seconds: u64,
+    pub max_turn_timeout_seconds: u64,
+    pub max_turn_grace_period_seconds: u64,
+}
+
+impl Default for GameRulesLimits {
+    fn default() -> Self {
+        Self {
+            min_starting_money: 500,
+            max_starting_money: 5_000,
+            max_go_salary: 1_000,
+            max_jail_fine: 500,
+            max_jail_turns: 5,
+            max_tax: 1_000,
+            max_total_houses: 64,
+            max_total_hotels: 24,
+            min_trade_expiry_seconds: 60,
+            max_trade_expiry_seconds: 86_400, // 1 day
+            min_turn_timeout_seconds: 10,
+            max_turn_timeout_seconds: 3_600, // 1 hour
+            max_turn_grace_period_seconds: 600,
+        }
+    }
+}
+
+impl GameRulesLimits {
+    pub fn validate_limits(&self) -> Result<()> {
+        require!(
+            self.min_starting_money <= self.max_starting_money
+                && self.max_jail_turns > 0
+                && self.min_trade_expiry_seconds > 0
+                && self.min_trade_expiry_seconds <= self.max_trade_expiry_seconds
+                && self.min_turn_timeout_seconds <= self.max_turn_timeout_seconds,
+            GameError::InvalidGameRules
+        );
+
+        Ok(())
+    }
+
+    pub fn validate_rules(&self, rules: &GameRules) -> Result<()> {
+        require!(
+            (self.min_starting_money..=self.max_starting_money).contains(&rules.starting_money),
+            GameError::InvalidGameRules
+        );
+        require!(
+            rules.go_salary <= self.max_go_salary,
+            GameError::InvalidGameRules
+        );
+        require!(
+            rules.jail_fine <= self.max_jail_fine,
+            GameError::InvalidGameRules
+        );
+        require!(
+            rules.max_jail_turns > 0 && rules.max_jail_turns <= self.max_jail_turns,
+            GameError::InvalidGameRules
+        );
+        require!(
+            rules.mev_tax <= self.max_tax && rules.priority_fee_tax <= self.max_tax,
+            GameError::InvalidGameRules
+        );
+        require!(
+            rules.total_houses <= self.max_total_houses
+                && rules.total_hotels <= self.max_total_hotels,
+            GameError::InvalidGameRules
+        );
+        require!(
+            (self.min_trade_expiry_seconds..=self.max_trade_expiry_seconds)
+                .contains(&rules.trade_expiry_seconds),
+            GameError::InvalidGameRules
+        );
+        require!(
+            (self.min_turn_timeout_seconds..=self.max_turn_timeout_seconds)
+                .contains(&rules.turn_timeout_seconds),
+            GameError::InvalidGameRules
+        );
+        require!(
+            rules.turn_grace_period_seconds <= self.max_turn_grace_period_seconds,
+            GameError::InvalidGameRules
+        );
+
+        Ok(())
+    }
+}
+
+// Ephemeral rollup the game and player accounts are delegated to
+#[derive(Debug, InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct DelegationSettings {
+    pub validator: Pubkey,
+    pub commit_frequency_ms: u32, // How often the ER commits state back to the base layer
+}
+
+impl Default for DelegationSettings {
+    fn default() -> Self {
+        Self {
+            validator: DEFAULT_ER_VALIDATOR,
+            commit_frequency_ms: DEFAULT_COMMIT_FREQUENCY_MS,
+        }
+    }
+}
+
+impl DelegationSettings {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.commit_frequency_ms > 0,
+            GameError::InvalidCommitFrequency
+        );
+        Ok(())
+    }
+
+    pub fn delegate_config(&self) -> DelegateConfig {
+        DelegateConfig {
+            commit_frequency_ms: self.commit_frequency_ms,
+            validator: Some(self.validator),
+        }
+    }
+}
+
+#[derive(Debug, InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum DeckKind {
+    Chance,
+    CommunityChest,
+}
+
+impl DeckKind {
+    pub fn seed(&self) -> &'static [u8] {
+        match self {
+            DeckKind::Chance => CHANCE_DECK_SEED,
+            DeckKind::CommunityChest => COMMUNITY_CHEST_DECK_SEED,
+        }
+    }
+}
+
+// Card definitions for one deck, shared by every game on the platform
+#[account]
+#[derive(Debug, InitSpace)]
+pub struct CardDeck {
+    pub platform: Pubkey, // PlatformConfig id
+    pub kind: DeckKind,
+    #[max_len(32)]
+    pub cards: Vec<CardData>,
+    pub bump: u8,
+}
+
+// Mint accepted for entry fees, with its own stake limits
+#[account]
+#[derive(Debug, InitSpace)]
+pub struct AllowedMint {
+    pub platform: Pubkey, // PlatformConfig id
+    pub mint: Pubkey,     // Pubkey::default() for native SOL
+    pub min_entry_fee: u64,
+    pub max_entry_fee: u64,
+    pub fee_basis_points_override: Option<u16>, // Replaces the platform fee for this mint
+    pub bump: u8,
+}
+
+impl AllowedMint {
+    pub fn validate_entry_fee(&self, entry_fee: u64) -> Result<()> {
+        require!(
+            entry_fee >= self.min_entry_fee && entry_fee <= self.max_entry_fee,
+            GameError::EntryFeeOutOfRange
+        );
+        Ok(())
     }
 }
 
 #[derive(Debug, InitSpace, AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
 pub enum GameStatus {
     WaitingForPlayers,
+    Starting, // Player accounts are being delegated in batches
     InProgress,
     Finished,
+    Cancelling, // Entry fees are being refunded in batches
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Debug, InitSpace, Clone, PartialEq, Eq)]
@@ -87,11 +332,95 @@ pub struct TradeInfo {
     pub trade_type: TradeType,         // Type of trade
     pub proposer_money: u64,           // Money offered by proposer
     pub receiver_money: u64,           // Money requested from receiver
-    pub proposer_property: Option<u8>, // Property offered by proposer
-    pub receiver_property: Option<u8>, // Property requested from receiver
-    pub status: TradeStatus,           // Current trade status
-    pub created_at: i64,               // Creation timestamp
-    pub expires_at: i64,               // Expiration timestamp
+    #[max_len(10)]
+    pub proposer_properties: Vec<u8>, // Properties offered by proposer
+    #[max_len(10)]
+    pub receiver_properties: Vec<u8>, // Properties requested from receiver
+    pub proposer_jail_cards: u8,      // Get-out-of-jail cards offered by proposer
+    pub receiver_jail_cards: u8,      // Get-out-of-jail cards requested from receiver
+    pub parent_trade_id: Option<u8>,  // Trade this one counters, if any
+    pub round: u8,                    // Negotiation round (0 = original offer)
+    pub status: TradeStatus,          // Current trade status
+    pub created_at: i64,              // Creation timestamp
+    pub expires_at: i64,              // Expiration timestamp
+}
+
+// Festival hosted on one street, boosting its rent while the host keeps it
+#[derive(Debug, InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct FestivalInfo {
+    pub property_position: u8,
+    pub host: Pubkey,
+}
+
+// Jail card kept by a player, identified by the deck it was drawn from
+#[derive(Debug, InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct HeldJailCard {
+    pub deck: DeckKind,
+    pub card_index: u8,
+}
+
+// Shuffled draw order for one card deck
+#[derive(Debug, InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct DeckState {
+    #[max_len(32)]
+    pub cards: Vec<CardData>, // Copied from the platform's CardDeck when the game was created
+    #[max_len(32)]
+    pub order: Vec<u8>, // Card indices in draw order
+    pub next_index: u8, // Position of the next card in `order`
+    #[max_len(4)]
+    pub held_cards: Vec<u8>, // Jail cards held by players, out of the deck until used
+}
+
+impl DeckState {
+    pub fn new(cards: Vec<CardData>) -> Self {
+        Self {
+            cards,
+            ..Default::default()
+        }
+    }
+
+    pub fn get_card(&self, card_index: usize) -> Result<&CardData> {
+        self.cards
+            .get(card_index)
+            .ok_or(GameError::InvalidParameter.into())
+    }
+
+    /// Draws the next card that isn't held by a player, reshuffling with
+    /// `seed` whenever the order runs out.
+    pub fn draw(&mut self, seed: u64) -> Result<usize> {
+        let deck_size = self.cards.len();
+        let mut reshuffles = 0;
+
+        require!(deck_size > 0, GameError::InvalidCardDeck);
+
+        loop {
+            if self.next_index as usize >= self.order.len() {
+                require!(reshuffles < 2, GameError::InvalidCardDeck);
+                self.shuffle(deck_size, seed.wrapping_add(reshuffles));
+                reshuffles += 1;
+            }
+
+            let card_index = self.order[self.next_index as usize];
+            self.next_index += 1;
+
+            if !self.held_cards.contains(&card_index) {
+                return Ok(card_index as usize);
+            }
+        }
+    }
+
+    /// Fisher-Yates shuffle of the whole deck
+    pub fn shuffle(&mut self, deck_size: usize, seed: u64) {
+        self.order = (0..deck_size as u8).collect();
+        self.next_index = 0;
+
+        let mut state = seed | 1; // xorshift needs a non-zero state
+        for i in (1..self.order.len()).rev() {
+            state = xorshift64star(state);
+            let j = (state % (i as u64 + 1)) as usize;
+            self.order.swap(i, j);
+        }
+    }
 }
 
 #[account]
@@ -104,10 +433,19 @@ pub struct GameState {
     pub max_players: u8,     // 1 byte - maximum players (2-8)
     pub current_players: u8, // 1 byte - current player count
     pub current_turn: u8,    // 1 byte - whose turn (player index)
-    #[max_len(4)]
+    #[max_len(8)]
     pub players: Vec<Pubkey>, // 32 * 8 = 256 bytes max
-    #[max_len(4)]
+    #[max_len(8)]
     pub player_eliminated: Vec<bool>, // Parallel array tracking elimination
+    pub players_delegated: u8, // Player accounts delegated so far by start_game
+    #[max_len(8)]
+    pub elimination_order: Vec<Pubkey>, // Bankrupt players, first eliminated first
+    #[max_len(8)]
+    pub final_ranking: Vec<Pubkey>, // Placements at game end, winner first
+    #[max_len(8)]
+    pub payout_bps: Vec<u16>, // Prize share per placement, winner first
+    #[max_len(8)]
+    pub prize_claims: Vec<bool>, // Parallel to payout_bps, share claimed
     pub total_players: u8,   // Total who joined (never decreases)
     pub active_players: u8,  // Current non-bankrupt count
     pub game_status: GameStatus, // 1 byte - current game status
@@ -122,9 +460,12 @@ pub struct GameState {
     pub token_mint: Option<Pubkey>, // 33 bytes - token mint for entry fee
     pub token_vault: Option<Pubkey>, // 33 bytes - vault holding entry fees
     pub total_prize_pool: u64, // 8 bytes - total collected fees
+    pub sponsor_pool: u64, // Part of total_prize_pool added by sponsors
+    pub sponsor_count: u8, // Sponsorship accounts not yet refunded
+    pub fee_basis_points: u16, // Platform fee on prizes, fixed at creation
 
     // pub is_ending: bool,     // 1 byte - game ending status
-    pub prize_claimed: bool,     // Track if reward claimed
+    pub prize_claimed: bool,     // Track if every paid placement claimed
     pub end_condition_met: bool, // Track if end condition met
 
     pub end_reason: Option<GameEndReason>, // How game ended
@@ -133,21 +474,115 @@ pub struct GameState {
     pub active_trades: Vec<TradeInfo>, // Vector of active trades
     pub next_trade_id: u8, // Next trade ID to assign
 
+    pub active_auction: Option<AuctionInfo>, // Open auction for a declined property
+    pub festival: Option<FestivalInfo>,      // Street with boosted rent
+
+    pub chance_deck: DeckState,          // Shuffled Chance cards
+    pub community_chest_deck: DeckState, // Shuffled Community Chest cards
+
     pub properties: [PropertyInfo; 40], // Fixed array: 40 × 36 bytes = 1,440 bytes
 
     pub created_at: i64, // 8 bytes - game creation timestamp
     pub started_at: Option<i64>,
     pub ended_at: Option<i64>,
     pub game_end_time: Option<i64>, // 8 bytes - game end time
-    pub turn_started_at: i64,       // 8 bytes - when current turn started
+    pub turn_started_at: i64,       // 8 bytes - when current turn started, or the last start batch
     pub time_limit: Option<i64>,    // 9 bytes - optional time limit
 
-    pub turn_timeout_seconds: u64, // 8 bytes - timeout duration (default 30)
-    pub turn_grace_period_seconds: u64, // 8 bytes - grace period (default 10)
+    pub rules: GameRules, // House rules, including turn timeouts
+    pub delegation: DelegationSettings, // ER validator and commit cadence, fixed at creation
+    pub use_vrf: bool, // VRF or commit-reveal randomness, fixed at creation
+    pub randomness_slot: u64, // Slot of the previous turn start or reveal, its hash seeds the next reveal
     pub timeout_enforcement_enabled: bool, // 1 byte - can disable for testing
 }
 
 impl GameState {
+    /// Account space for a game capped at `max_players`, without the discriminator
+    pub fn space_for(max_players: u8) -> usize {
+        let unused_slots = crate::constants::MAX_PLAYERS.saturating_sub(max_players) as usize;
+        // players, player_eliminated, elimination_order, final_ranking, payout_bps, prize_claims
+        Self::INIT_SPACE - unused_slots * (32 + 1 + 32 + 32 + 2 + 1)
+    }
+
+    /// A payout schedule pays 1 to `max_players` places and splits the whole pool
+    pub fn validate_payout_schedule(payout_bps: &[u16], max_players: u8) -> Result<()> {
+        require!(
+            !payout_bps.is_empty() && payout_bps.len() <= max_players as usize,
+            GameError::InvalidPayoutSchedule
+        );
+        require!(
+            payout_bps.iter().all(|&bps| bps > 0),
+            GameError::InvalidPayoutSchedule
+        );
+        require!(
+            payout_bps.iter().map(|&bps| bps as u32).sum::<u32>() == TOTAL_BASIS_POINTS as u32,
+            GameError::InvalidPayoutSchedule
+        );
+        Ok(())
+    }
+
+    /// Token games and sponsored freerolls hold their prize pool in a token vault
+    pub fn uses_token_vault(&self) -> bool {
+        self.token_vault.is_some()
+    }
+
+    /// Paid games without a token mint escrow their entry fees as lamports
+    pub fn uses_sol_vault(&self) -> bool {
+        self.entry_fee > 0 && self.token_mint.is_none()
+    }
+
+    /// A finished game can be closed once every prize is paid or the claim window has passed
+    pub fn claims_settled(&self, now: i64) -> bool {
+        self.total_prize_pool == 0
+            || self.prize_claimed
+            || self.ended_at.is_some_and(|ended_at| {
+                now >= ended_at.saturating_add(crate::constants::CLAIM_WINDOW_SECONDS)
+            })
+    }
+
+    /// Ranks the survivors in the given order, followed by eliminated players
+    /// from last to first eliminated
+    pub fn record_final_ranking(&mut self, survivors: &[Pubkey]) {
+        self.final_ranking = survivors
+            .iter()
+            .chain(self.elimination_order.iter().rev())
+            .copied()
+            .collect();
+    }
+
+    /// Prize for a placement. Shares of places nobody finished in, and any
+    /// rounding remainder, go to the winner.
+    pub fn prize_share(&self, placement: usize) -> Result<u64> {
+        let paid_places = self.payout_bps.len().min(self.final_ranking.len());
+        if placement >= paid_places {
+            return Ok(0);
+        }
+
+        let share_of = |bps: u16| -> Result<u64> {
+            let share = (self.total_prize_pool as u128)
+                .checked_mul(bps as u128)
+                .ok_or(GameError::ArithmeticOverflow)?
+                / TOTAL_BASIS_POINTS as u128;
+            Ok(share as u64)
+        };
+
+        if placement > 0 {
+            return share_of(self.payout_bps[placement]);
+        }
+
+        let mut runner_up_shares: u64 = 0;
+        for &bps in &self.payout_bps[1..paid_places] {
+            runner_up_shares = runner_up_shares
+                .checked_add(share_of(bps)?)
+                .ok_or(GameError::ArithmeticOverflow)?;
+        }
+
+        Ok(self
+            .total_prize_pool
+            .checked_sub(runner_up_shares)
+            .ok_or(GameError::ArithmeticUnderflow)?)
+    }
+
     pub fn cleanup_expired_trades(&mut self, current_time: i64) {
         self.active_trades.retain(|trade| {
             trade.expires_at > current_time && trade.status == TradeStatus::Pending
@@ -187,6 +622,76 @@ impl GameState {
         id
     }
 
+    /// Adds a payment made to the bank to the Free Parking jackpot when that
+    /// house rule is on
+    pub fn add_to_free_parking(&mut self, amount: u64) {
+        if self.rules.free_parking_jackpot {
+            self.free_parking_pool = self.free_parking_pool.saturating_add(amount);
+        }
+    }
+
+    pub fn is_festival_property(&self, position: u8) -> bool {
+        match self.festival {
+            Some(festival) => {
+                festival.property_position == position
+                    && self.properties[position as usize].owner == Some(festival.host)
+            }
+            None => false,
+        }
+    }
+
+    pub fn has_active_auction(&self) -> bool {
+        self.active_auction.is_some()
+    }
+
+    // card decks
+    pub fn deck_state_mut(&mut self, kind: DeckKind) -> &mut DeckState {
+        match kind {
+            DeckKind::Chance => &mut self.chance_deck,
+            DeckKind::CommunityChest => &mut self.community_chest_deck,
+        }
+    }
+
+    /// Draws a card and returns its index together with a copy of the card
+    pub fn draw_card(&mut self, kind: DeckKind, seed: u64) -> Result<(usize, CardData)> {
+        let card_index = self.deck_state_mut(kind).draw(seed)?;
+        let card = self.take_card(kind, card_index)?;
+        Ok((card_index, card))
+    }
+
+    /// Keeps a drawn jail card out of its deck while a player holds it
+    pub fn take_card(&mut self, kind: DeckKind, card_index: usize) -> Result<CardData> {
+        let deck_state = self.deck_state_mut(kind);
+        let card = *deck_state.get_card(card_index)?;
+
+        if card.effect_type == CardEffectType::GetOutOfJailFree {
+            require!(
+                !deck_state.held_cards.contains(&(card_index as u8)),
+                GameError::JailCardAlreadyHeld
+            );
+            require!(
+                deck_state.held_cards.len() < MAX_JAIL_CARDS_PER_DECK,
+                GameError::InvalidCardDeck
+            );
+            deck_state.held_cards.push(card_index as u8);
+        }
+
+        Ok(card)
+    }
+
+    /// Puts a used or forfeited jail card back into the deck it came from
+    pub fn return_jail_card(&mut self, card: HeldJailCard) {
+        let deck_state = self.deck_state_mut(card.deck);
+
+        if let Some(held_index) = deck_state
+            .held_cards
+            .iter()
+            .position(|&held| held == card.card_index)
+        {
+            deck_state.held_cards.remove(held_index);
+        }
+    }
+
     // properties
     pub fn initialize_properties(&mut self) {
         self.properties = [PropertyInfo::default(); 40];
@@ -348,6 +853,12 @@ impl GameState {
         active_count <= 1
     }
 
+    /// A start that made no progress for `START_TIMEOUT_SECONDS`
+    pub fn is_start_stalled(&self, current_time: i64) -> bool {
+        self.game_status == GameStatus::Starting
+            && current_time >= self.turn_started_at.saturating_add(START_TIMEOUT_SECONDS)
+    }
+
     /// Check if game should end due to time limit
     pub fn check_time_end_condition(&self, current_time: i64) -> bool {
         if let Some(end_time) = self.game_end_time {
@@ -370,6 +881,9 @@ impl GameState {
     pub fn advance_turn(&mut self) -> Result<()> {
         let mut attempts = 0;
 
+        // The next player's first reveal is mixed with this slot's hash
+        self.randomness_slot = Clock::get()?.slot;
+
         loop {
             self.current_turn = (self.current_turn + 1) % self.players.len() as u8;
 
@@ -388,6 +902,16 @@ impl GameState {
     }
 }
 
+// Third party contribution to a game's prize pool, refunded if the game is cancelled
+#[account]
+#[derive(Debug, InitSpace)]
+pub struct Sponsorship {
+    pub game: Pubkey,
+    pub sponsor: Pubkey,
+    pub amount: u64, // Credited to the pool, net of transfer fees
+    pub bump: u8,
+}
+
 #[account]
 #[derive(Debug, InitSpace)]
 pub struct PlayerState {
@@ -401,7 +925,8 @@ pub struct PlayerState {
     pub is_bankrupt: bool, // 1 byte - bankruptcy status
     #[max_len(40)]
     pub properties_owned: Vec<u8>, // variable - owned property positions
-    pub get_out_of_jail_cards: u8, // 1 byte - jail cards owned
+    #[max_len(8)]
+    pub get_out_of_jail_cards: Vec<HeldJailCard>, // jail cards owned, at most 4 per deck
     pub net_worth: u64,    // 8 bytes - total asset value
     pub last_rent_collected: i64, // 8 bytes - last rent collection time
     pub festival_boost_turns: u8, // 1 byte - remaining festival boost turns
@@ -413,15 +938,22 @@ pub struct PlayerState {
     pub needs_chance_card: bool,     // Needs to draw chance card
     pub needs_community_chest_card: bool, // Needs to draw community chest
     pub needs_bankruptcy_check: bool, // Insufficient funds detected
+    pub debt_creditor: Option<Pubkey>, // Who the unpaid debt is owed to (None = bank)
+    pub debt_amount: u64,             // Amount of the unpaid debt
     // pub can_end_turn: bool,          // All actions completed
     pub needs_special_space_action: bool, // Player landed on special space
     pub pending_special_space_position: Option<u8>, // Which special space
 
     pub card_drawn_at: Option<i64>, // Timestamp when card was drawn
+    pub double_rent_pending: bool,  // Card move owes double rent on arrival
 
     pub timeout_penalty_count: u8, // 1 byte - number of timeout penalties
     pub last_action_timestamp: i64, // 8 bytes - last action taken
     pub total_timeout_penalties: u8, // 1 byte - lifetime count for stats
+
+    pub entry_deposit: u64, // Entry fee credited to the pool, net of transfer fees
+
+    pub randomness_commitment: Option<[u8; 32]>, // Head of the commit-reveal hash chain
 }
 
 impl PlayerState {
@@ -429,19 +961,20 @@ impl PlayerState {
         self: &mut PlayerState,
         wallet: Pubkey,
         game: Pubkey,
+        starting_money: u64,
         clock: &Sysvar<Clock>,
     ) {
         self.wallet = wallet;
         self.game = game;
-        self.cash_balance = STARTING_MONEY as u64;
+        self.cash_balance = starting_money;
         self.position = 0;
         self.in_jail = false;
         self.jail_turns = 0;
         self.doubles_count = 0;
         self.is_bankrupt = false;
         self.properties_owned = Vec::new();
-        self.get_out_of_jail_cards = 0;
-        self.net_worth = STARTING_MONEY as u64;
+        self.get_out_of_jail_cards = Vec::new();
+        self.net_worth = starting_money;
         self.last_rent_collected = clock.unix_timestamp;
         self.festival_boost_turns = 0;
         self.has_rolled_dice = false;
@@ -451,19 +984,109 @@ impl PlayerState {
         self.needs_chance_card = false;
         self.needs_community_chest_card = false;
         self.needs_bankruptcy_check = false;
+        self.debt_creditor = None;
+        self.debt_amount = 0;
         self.needs_special_space_action = false;
         self.pending_special_space_position = None;
         self.card_drawn_at = None;
+        self.double_rent_pending = false;
 
         self.timeout_penalty_count = 0;
         self.last_action_timestamp = clock.unix_timestamp;
         self.total_timeout_penalties = 0;
+
+        self.entry_deposit = 0;
+
+        self.randomness_commitment = None;
+    }
+
+    /// Checks `reveal` against the committed hash chain and makes it the new
+    /// head, so each value can only be revealed once.
+    pub fn reveal_randomness(&mut self, reveal: [u8; 32]) -> Result<()> {
+        let commitment = self
+            .randomness_commitment
+            .ok_or(GameError::MissingRandomnessCommitment)?;
+
+        require!(
+            hash(&reveal).to_bytes() == commitment,
+            GameError::InvalidRandomnessReveal
+        );
+
+        self.randomness_commitment = Some(reveal);
+        Ok(())
     }
 
     pub fn record_action(&mut self, clock: &Sysvar<Clock>) {
         self.last_action_timestamp = clock.unix_timestamp;
     }
 
+    pub fn jail_card_count(&self) -> u8 {
+        self.get_out_of_jail_cards.len() as u8
+    }
+
+    /// Removes the `count` most recently received jail cards
+    pub fn take_jail_cards(&mut self, count: u8) -> Result<Vec<HeldJailCard>> {
+        let remaining = self
+            .get_out_of_jail_cards
+            .len()
+            .checked_sub(count as usize)
+            .ok_or(GameError::ArithmeticUnderflow)?;
+
+        Ok(self.get_out_of_jail_cards.split_off(remaining))
+    }
+
+    /// A player owes a single creditor at a time
+    pub fn can_record_debt(&self, creditor: Option<Pubkey>) -> bool {
+        !self.needs_bankruptcy_check || self.debt_creditor == creditor
+    }
+
+    /// Flags an unpaid debt for the bankruptcy check. `creditor` is `None`
+    /// when the money is owed to the bank. A further debt to the same
+    /// creditor adds up, one to a different creditor must wait until the
+    /// outstanding debt is settled.
+    pub fn record_debt(&mut self, creditor: Option<Pubkey>, amount: u64) -> Result<()> {
+        if self.needs_bankruptcy_check {
+            require!(self.can_record_debt(creditor), GameError::DebtOutstanding);
+        } else {
+            self.debt_creditor = creditor;
+            self.debt_amount = 0;
+        }
+
+        self.needs_bankruptcy_check = true;
+        self.debt_amount = self
+            .debt_amount
+            .checked_add(amount)
+            .ok_or(GameError::ArithmeticOverflow)?;
+
+        Ok(())
+    }
+
+    /// Whether the player stands on a tax square they haven't paid yet
+    pub fn has_pending_tax(&self) -> bool {
+        self.needs_special_space_action
+            && matches!(
+                self.pending_special_space_position,
+                Some(MEV_TAX_POSITION | PRIORITY_FEE_TAX_POSITION)
+            )
+    }
+
+    /// Resolves the landing a settled debt came from: the rent for a debt to
+    /// a player, the unpaid tax for a debt to the bank
+    pub fn resolve_settled_landing(&mut self) {
+        if self.debt_creditor.is_some() {
+            self.needs_property_action = false;
+        } else if self.has_pending_tax() {
+            self.needs_special_space_action = false;
+            self.pending_special_space_position = None;
+        }
+    }
+
+    pub fn clear_debt(&mut self) {
+        self.needs_bankruptcy_check = false;
+        self.debt_creditor = None;
+        self.debt_amount = 0;
+    }
+
     pub fn has_recent_activity(&self, current_time: i64, grace_period: u64) -> bool {
         let elapsed = current_time.saturating_sub(self.last_action_timestamp);
         elapsed < grace_period as i64
@@ -500,6 +1123,7 @@ pub enum TradeType {
     PropertyOnly,
     MoneyForProperty,
     PropertyForMoney,
+    Bundle, // Any mix of money, properties and jail cards
 }
 
 #[account]
@@ -526,21 +1150,21 @@ pub struct TradeState {
 
 impl TradeState {}
 
-#[account]
-#[derive(Debug, InitSpace)]
-pub struct AuctionState {
-    pub game: Pubkey,
-    pub property_position: u8,
-    pub current_bid: u64,
-    pub highest_bidder: Option<Pubkey>,
-    pub started_at: i64,
-    pub ends_at: i64,
-    pub is_active: bool,
-    pub bump: u8,
+// Auction for a declined property, stored in GameState while bidding is open
+#[derive(Debug, InitSpace, AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct AuctionInfo {
+    pub property_position: u8,          // Property being auctioned
+    pub initiator: Pubkey,              // Player who declined the property
+    pub current_bid: u64,               // Highest bid so far
+    pub highest_bidder: Option<Pubkey>, // Current highest bidder
+    pub started_at: i64,                // Auction start timestamp
+    pub ends_at: i64,                   // Bidding deadline
 }
 
 // ----- new struct
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, InitSpace, PartialEq, Eq)]
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, InitSpace, PartialEq, Eq,
+)]
 pub struct PropertyInfo {
     pub owner: Option<Pubkey>, // 33 bytes - who owns this property
     pub houses: u8,            // 1 byte - number of houses (0-4)
@@ -548,19 +1172,296 @@ pub struct PropertyInfo {
     pub is_mortgaged: bool,    // 1 byte - mortgage status
 }
 
-impl Default for PropertyInfo {
-    fn default() -> Self {
-        Self {
-            owner: None,
-            houses: 0,
-            has_hotel: false,
-            is_mortgaged: false,
-        }
-    }
-}
-
 impl PropertyInfo {
     pub fn initialize_all() -> Vec<PropertyInfo> {
         vec![PropertyInfo::default(); 40]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{find_slot_hash, SlotHashLookup, DEFAULT_CHANCE_CARDS};
+
+    fn finished_game(total_prize_pool: u64, payout_bps: &[u16], finishers: usize) -> GameState {
+        let zeroed = vec![0u8; GameState::INIT_SPACE];
+        let mut game = GameState::deserialize(&mut zeroed.as_slice()).unwrap();
+
+        game.total_prize_pool = total_prize_pool;
+        game.payout_bps = payout_bps.to_vec();
+        game.final_ranking = (0..finishers).map(|_| Pubkey::new_unique()).collect();
+        game
+    }
+
+    fn new_player() -> PlayerState {
+        let zeroed = vec![0u8; PlayerState::INIT_SPACE];
+        PlayerState::deserialize(&mut zeroed.as_slice()).unwrap()
+    }
+
+    fn chance_deck() -> DeckState {
+        DeckState::new(DEFAULT_CHANCE_CARDS.to_vec())
+    }
+
+    fn jail_card_index(deck: &DeckState) -> usize {
+        deck.cards
+            .iter()
+            .position(|card| card.effect_type == CardEffectType::GetOutOfJailFree)
+            .unwrap()
+    }
+
+    /// Raw SlotHashes data holding `slots`, newest first, with spare zeroed
+    /// entries past the count like the real sysvar account
+    fn slot_hashes_data(slots: &[u64]) -> Vec<u8> {
+        let mut data = (slots.len() as u64).to_le_bytes().to_vec();
+        for &slot in slots {
+            data.extend_from_slice(&slot.to_le_bytes());
+            data.extend_from_slice(&[slot as u8; 32]);
+        }
+        data.extend_from_slice(&[0u8; 80]);
+        data
+    }
+
+    fn all_shares(game: &GameState) -> Vec<u64> {
+        (0..game.payout_bps.len())
+            .map(|placement| game.prize_share(placement).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn validate_payout_schedule_accepts_full_split() {
+        assert!(GameState::validate_payout_schedule(&[10_000], 2).is_ok());
+        assert!(GameState::validate_payout_schedule(&[6_000, 3_000, 1_000], 4).is_ok());
+    }
+
+    #[test]
+    fn validate_payout_schedule_rejects_bad_schedules() {
+        // No places, or more places than seats
+        assert!(GameState::validate_payout_schedule(&[], 4).is_err());
+        assert!(GameState::validate_payout_schedule(&[4_000, 3_000, 2_000, 1_000], 3).is_err());
+        // A paid place must pay something
+        assert!(GameState::validate_payout_schedule(&[10_000, 0], 4).is_err());
+        // The whole pool, no more and no less
+        assert!(GameState::validate_payout_schedule(&[6_000, 3_000], 4).is_err());
+        assert!(GameState::validate_payout_schedule(&[6_000, 5_000], 4).is_err());
+    }
+
+    #[test]
+    fn prize_share_gives_rounding_remainder_to_winner() {
+        let game = finished_game(1_001, &[5_000, 3_333, 1_667], 3);
+
+        // 1001 * 33.33% = 333.6 and 1001 * 16.67% = 166.9 both round down
+        assert_eq!(all_shares(&game), vec![502, 333, 166]);
+        assert_eq!(all_shares(&game).iter().sum::<u64>(), 1_001);
+    }
+
+    #[test]
+    fn prize_share_gives_unfinished_places_to_winner() {
+        let game = finished_game(1_000, &[5_000, 3_000, 2_000], 2);
+
+        assert_eq!(all_shares(&game), vec![700, 300, 0]);
+        assert_eq!(all_shares(&game).iter().sum::<u64>(), 1_000);
+    }
+
+    #[test]
+    fn prize_share_is_zero_outside_paid_places() {
+        let game = finished_game(1_000, &[7_000, 3_000], 4);
+
+        assert_eq!(game.prize_share(1).unwrap(), 300);
+        assert_eq!(game.prize_share(2).unwrap(), 0);
+        assert_eq!(game.prize_share(3).unwrap(), 0);
+    }
+
+    #[test]
+    fn settling_an_unpaid_tax_resolves_the_landing() {
+        let mut player = new_player();
+
+        // Landed on the MEV tax without the cash to pay it
+        player.position = MEV_TAX_POSITION;
+        player.needs_special_space_action = true;
+        player.pending_special_space_position = Some(MEV_TAX_POSITION);
+        player.record_debt(None, MEV_TAX as u64).unwrap();
+        assert!(player.has_pending_tax());
+
+        // settle_debt resolves the landing, so end_turn has nothing left to refuse
+        player.resolve_settled_landing();
+        player.clear_debt();
+        assert!(!player.needs_bankruptcy_check);
+        assert!(!player.has_pending_tax());
+        assert_eq!(player.pending_special_space_position, None);
+    }
+
+    #[test]
+    fn shuffle_is_a_permutation_of_the_deck() {
+        let mut deck = chance_deck();
+
+        for seed in 0..32 {
+            deck.next_index = 5;
+            deck.shuffle(deck.cards.len(), seed);
+
+            let mut order = deck.order.clone();
+            order.sort_unstable();
+            assert_eq!(order, (0..deck.cards.len() as u8).collect::<Vec<_>>());
+            assert_eq!(deck.next_index, 0);
+        }
+    }
+
+    #[test]
+    fn draw_deals_every_card_once_per_shuffle() {
+        let mut deck = chance_deck();
+        let deck_size = deck.cards.len();
+
+        let mut drawn: Vec<usize> = (0..deck_size).map(|_| deck.draw(7).unwrap()).collect();
+        drawn.sort_unstable();
+        assert_eq!(drawn, (0..deck_size).collect::<Vec<_>>());
+
+        // The next draw reshuffles
+        deck.draw(8).unwrap();
+        assert_eq!(deck.next_index, 1);
+    }
+
+    #[test]
+    fn draw_skips_held_jail_cards() {
+        let mut deck = chance_deck();
+        let jail_card = jail_card_index(&deck);
+        deck.held_cards.push(jail_card as u8);
+
+        for seed in 0..3 * deck.cards.len() as u64 {
+            assert_ne!(deck.draw(seed).unwrap(), jail_card);
+        }
+    }
+
+    #[test]
+    fn draw_stops_reshuffling_when_every_card_is_held() {
+        let mut empty = DeckState::new(vec![]);
+        assert!(empty.draw(1).is_err());
+
+        let jail_card = DEFAULT_CHANCE_CARDS[jail_card_index(&chance_deck())];
+        let mut deck = DeckState::new(vec![jail_card; 2]);
+        deck.held_cards = vec![0, 1];
+        assert!(deck.draw(1).is_err());
+    }
+
+    #[test]
+    fn jail_card_stays_out_of_the_deck_until_returned() {
+        let mut game = finished_game(0, &[], 0);
+        game.chance_deck = chance_deck();
+        let jail_card = jail_card_index(&game.chance_deck);
+
+        game.take_card(DeckKind::Chance, jail_card).unwrap();
+        assert!(game.take_card(DeckKind::Chance, jail_card).is_err());
+
+        game.return_jail_card(HeldJailCard {
+            deck: DeckKind::Chance,
+            card_index: jail_card as u8,
+        });
+        assert!(game.chance_deck.held_cards.is_empty());
+        assert!(game.take_card(DeckKind::Chance, jail_card).is_ok());
+    }
+
+    #[test]
+    fn record_debt_adds_up_per_creditor() {
+        let mut player = new_player();
+        let creditor = Pubkey::new_unique();
+
+        player.record_debt(Some(creditor), 100).unwrap();
+        player.record_debt(Some(creditor), 50).unwrap();
+        assert!(player.needs_bankruptcy_check);
+        assert_eq!(player.debt_creditor, Some(creditor));
+        assert_eq!(player.debt_amount, 150);
+
+        // Another creditor, the bank included, waits for this debt
+        assert!(player.record_debt(None, 10).is_err());
+        assert!(player.record_debt(Some(Pubkey::new_unique()), 10).is_err());
+        assert_eq!(player.debt_amount, 150);
+
+        player.clear_debt();
+        player.record_debt(None, 10).unwrap();
+        assert_eq!(player.debt_creditor, None);
+        assert_eq!(player.debt_amount, 10);
+    }
+
+    #[test]
+    fn reveal_randomness_walks_the_hash_chain() {
+        let mut player = new_player();
+        let seed = [7u8; 32];
+        let first = hash(&seed).to_bytes();
+        let head = hash(&first).to_bytes();
+
+        assert!(player.reveal_randomness(first).is_err());
+
+        player.randomness_commitment = Some(head);
+        assert!(player.reveal_randomness(seed).is_err());
+        player.reveal_randomness(first).unwrap();
+
+        // Each value is revealed once, the next one is its preimage
+        assert!(player.reveal_randomness(first).is_err());
+        player.reveal_randomness(seed).unwrap();
+        assert_eq!(player.randomness_commitment, Some(seed));
+    }
+
+    #[test]
+    fn validate_rules_accepts_defaults_within_limits() {
+        let limits = GameRulesLimits::default();
+        assert!(limits.validate_rules(&GameRules::default()).is_ok());
+    }
+
+    #[test]
+    fn validate_rules_rejects_rules_outside_limits() {
+        let limits = GameRulesLimits::default();
+        let out_of_limits = [
+            GameRules {
+                starting_money: limits.max_starting_money + 1,
+                ..Default::default()
+            },
+            GameRules {
+                go_salary: limits.max_go_salary + 1,
+                ..Default::default()
+            },
+            GameRules {
+                max_jail_turns: 0,
+                ..Default::default()
+            },
+            GameRules {
+                priority_fee_tax: limits.max_tax + 1,
+                ..Default::default()
+            },
+            GameRules {
+                total_hotels: limits.max_total_hotels + 1,
+                ..Default::default()
+            },
+            GameRules {
+                trade_expiry_seconds: limits.min_trade_expiry_seconds - 1,
+                ..Default::default()
+            },
+            GameRules {
+                turn_timeout_seconds: limits.max_turn_timeout_seconds + 1,
+                ..Default::default()
+            },
+        ];
+
+        for rules in out_of_limits.iter() {
+            assert!(limits.validate_rules(rules).is_err());
+        }
+    }
+
+    #[test]
+    fn find_slot_hash_reads_slot_hashes_entries() {
+        let data = slot_hashes_data(&[102, 101, 100]);
+
+        assert_eq!(find_slot_hash(&data, 101), SlotHashLookup::Found([101; 32]));
+        assert_eq!(find_slot_hash(&data, 100), SlotHashLookup::Found([100; 32]));
+        assert_eq!(find_slot_hash(&data, 103), SlotHashLookup::Pending);
+        assert_eq!(find_slot_hash(&data, 99), SlotHashLookup::Expired);
+        // Zeroed space past the entry count isn't an entry for slot 0
+        assert_eq!(find_slot_hash(&data, 0), SlotHashLookup::Expired);
+    }
+
+    #[test]
+    fn find_slot_hash_handles_empty_data() {
+        assert_eq!(find_slot_hash(&[], 5), SlotHashLookup::Pending);
+        assert_eq!(
+            find_slot_hash(&slot_hashes_data(&[]), 5),
+            SlotHashLookup::Pending
+        );
+    }
+}
@@ -1,8 +1,20 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        transfer_fee::TransferFeeAmount, BaseStateWithExtensions, ExtensionType,
+        StateWithExtensions,
+    },
+};
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{harvest_withheld_tokens_to_mint, HarvestWithheldTokensToMint};
 
 use crate::{
-    error::GameError, get_property_data, ColorGroup, GameState, PlayerState, PropertyType,
-    SpecialSpaceAction, JAIL_POSITION,
+    error::GameError, get_property_data, BeachResortBonusPaid, ColorGroup, FestivalEnded,
+    FreeParkingCollected, GameState, PlayerState, PropertyType, SpecialSpaceAction,
+    BEACH_RESORT_BONUS_PER_PROPERTY, FESTIVAL_RENT_MULTIPLIER, JAIL_POSITION, SOL_VAULT_SEED,
 };
 
 // Helper function for rent calculation
@@ -139,49 +151,68 @@ pub fn can_sell_evenly_for_player(
     true
 }
 
-// Helper function to generate random card index
-pub fn generate_card_index(
-    recent_blockhashes: &UncheckedAccount,
-    timestamp: i64,
-    deck_size: usize,
-) -> Result<usize> {
-    // Get recent blockhash data for randomness
-    let data = recent_blockhashes.try_borrow_data()?;
-
-    // Use a combination of blockhash and timestamp for randomness
-    let mut seed_bytes = [0u8; 32];
-
-    // Take first 24 bytes from recent blockhash data
-    if data.len() >= 24 {
-        seed_bytes[..24].copy_from_slice(&data[..24]);
+#[derive(Debug, PartialEq, Eq)]
+pub enum SlotHashLookup {
+    Found([u8; 32]),
+    Pending, // The slot hasn't completed yet
+    Expired, // The slot has aged out of the sysvar
+}
+
+/// Looks `slot` up in the raw SlotHashes sysvar data: a u64 entry count
+/// followed by (slot, hash) pairs, newest first.
+pub fn find_slot_hash(data: &[u8], slot: u64) -> SlotHashLookup {
+    let count = data
+        .get(..8)
+        .map_or(0, |count| u64::from_le_bytes(count.try_into().unwrap()));
+    let entries = data
+        .get(8..)
+        .unwrap_or_default()
+        .chunks_exact(40)
+        .take(count as usize);
+
+    let mut newest_slot = None;
+    for entry in entries {
+        let (entry_slot, hash) = entry.split_at(8);
+        let entry_slot = u64::from_le_bytes(entry_slot.try_into().unwrap());
+
+        if entry_slot == slot {
+            return SlotHashLookup::Found(hash.try_into().unwrap());
+        }
+        newest_slot.get_or_insert(entry_slot);
     }
 
-    // Add timestamp to last 8 bytes
-    let timestamp_bytes = timestamp.to_le_bytes();
-    seed_bytes[24..].copy_from_slice(&timestamp_bytes);
+    match newest_slot {
+        Some(newest_slot) if slot <= newest_slot => SlotHashLookup::Expired,
+        _ => SlotHashLookup::Pending,
+    }
+}
 
-    // Generate random index
-    let random_value =
-        u32::from_le_bytes([seed_bytes[0], seed_bytes[1], seed_bytes[2], seed_bytes[3]]);
+// Checks the player's next commit-reveal value and mixes it with the hash of
+// the slot recorded at the previous action. That hash didn't exist yet when
+// the action was taken, so the player can't time the reveal to pick a result.
+// The current slot is recorded for the next reveal.
+pub fn commit_reveal_seed(
+    game: &mut GameState,
+    player_state: &mut PlayerState,
+    slot_hashes: &UncheckedAccount,
+    reveal: [u8; 32],
+    current_slot: u64,
+) -> Result<u64> {
+    player_state.reveal_randomness(reveal)?;
 
-    Ok((random_value as usize) % deck_size)
-}
+    let slot_hash = match find_slot_hash(&slot_hashes.try_borrow_data()?, game.randomness_slot) {
+        SlotHashLookup::Found(hash) => hash,
+        _ => return Err(GameError::RandomnessUnavailable.into()),
+    };
 
-// Helper function for generating random seed (similar to dice roll generation)
-pub fn generate_random_seed(recent_blockhashes: &UncheckedAccount, timestamp: i64) -> Result<u64> {
-    let recent_blockhashes_data = recent_blockhashes.try_borrow_data()?;
+    game.randomness_slot = current_slot;
 
-    if recent_blockhashes_data.len() < 8 {
-        return Err(GameError::RandomnessUnavailable.into());
-    }
+    let seed = hashv(&[&reveal, &slot_hash]).to_bytes();
 
     let mut seed_bytes = [0u8; 8];
-    seed_bytes.copy_from_slice(&recent_blockhashes_data[0..8]);
-
-    let blockhash_seed = u64::from_le_bytes(seed_bytes);
-    let timestamp_seed = timestamp as u64;
+    seed_bytes.copy_from_slice(&seed[0..8]);
 
-    Ok(blockhash_seed.wrapping_mul(timestamp_seed))
+    Ok(u64::from_le_bytes(seed_bytes))
 }
 
 pub fn xorshift64star(seed: u64) -> u64 {
@@ -215,6 +246,7 @@ pub fn send_player_to_jail_and_end_turn(
 
     // Automatically end turn
     player_state.has_rolled_dice = false;
+    tick_festival_boost(game, player_state, clock);
 
     // Advance to next player
     // let next_turn = (game.current_turn + 1) % game.current_players;
@@ -252,8 +284,8 @@ pub fn send_player_to_jail(player_state: &mut PlayerState) {
 }
 
 pub fn force_end_turn_util(
-    game: &mut GameState,
-    player_state: &mut PlayerState,
+    game: &mut Box<Account<'_, GameState>>,
+    player_state: &mut Box<Account<'_, PlayerState>>,
     clock: &Sysvar<Clock>,
 ) {
     // Reset turn-specific flags
@@ -264,9 +296,11 @@ pub fn force_end_turn_util(
     player_state.needs_community_chest_card = false;
     player_state.needs_special_space_action = false;
     player_state.pending_special_space_position = None;
+    player_state.double_rent_pending = false;
 
     // Reset doubles count when turn ends
     player_state.doubles_count = 0;
+    tick_festival_boost(game, player_state, clock);
 
     // Advance to next player
     game.advance_turn().unwrap();
@@ -278,6 +312,288 @@ pub fn force_end_turn_util(
     );
 }
 
+/// Counts down the festival boost at the end of each of the host's turns and
+/// ends the festival once it runs out
+pub fn tick_festival_boost(
+    game: &mut Box<Account<'_, GameState>>,
+    player_state: &mut Box<Account<'_, PlayerState>>,
+    clock: &Sysvar<Clock>,
+) {
+    if player_state.festival_boost_turns == 0 {
+        return;
+    }
+
+    player_state.festival_boost_turns -= 1;
+
+    if player_state.festival_boost_turns > 0 {
+        return;
+    }
+
+    let host = player_state.wallet;
+    if let Some(festival) = game.festival.filter(|f| f.host == host) {
+        game.festival = None;
+
+        emit!(FestivalEnded {
+            game: game.key(),
+            host,
+            property_position: festival.property_position,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+}
+
+/// Loads the PlayerState of every other active player from `remaining_accounts`.
+/// Each account must be the player PDA for this game, and every active
+/// opponent of `drawer` has to be passed exactly once.
+pub fn load_other_player_states<'info>(
+    game: &GameState,
+    game_key: &Pubkey,
+    drawer: &Pubkey,
+    remaining_accounts: &'info [AccountInfo<'info>],
+) -> Result<Vec<Account<'info, PlayerState>>> {
+    let mut other_players: Vec<Account<'info, PlayerState>> = Vec::new();
+
+    for account_info in remaining_accounts {
+        let player_state = Account::<PlayerState>::try_from(account_info)?;
+
+        let (expected_key, _) = Pubkey::find_program_address(
+            &[b"player", game_key.as_ref(), player_state.wallet.as_ref()],
+            &crate::ID,
+        );
+        require!(
+            account_info.key() == expected_key,
+            GameError::InvalidPlayerAccount
+        );
+
+        let player_index = game
+            .players
+            .iter()
+            .position(|&p| p == player_state.wallet)
+            .ok_or(GameError::PlayerNotFound)?;
+
+        require!(
+            player_state.wallet != *drawer
+                && !game.player_eliminated[player_index]
+                && !other_players
+                    .iter()
+                    .any(|p| p.wallet == player_state.wallet),
+            GameError::InvalidPlayerAccount
+        );
+
+        other_players.push(player_state);
+    }
+
+    let active_opponents = game
+        .players
+        .iter()
+        .zip(game.player_eliminated.iter())
+        .filter(|(&p, &eliminated)| p != *drawer && !eliminated)
+        .count();
+    require!(
+        other_players.len() == active_opponents,
+        GameError::MissingPlayerAccount
+    );
+
+    Ok(other_players)
+}
+
+/// Pays the Free Parking jackpot to the player who landed on it
+pub fn collect_free_parking(
+    game: &mut GameState,
+    game_key: Pubkey,
+    player_state: &mut PlayerState,
+    clock: &Sysvar<Clock>,
+) -> Result<()> {
+    let amount = game.free_parking_pool;
+    if amount == 0 {
+        return Ok(());
+    }
+
+    player_state.cash_balance = player_state
+        .cash_balance
+        .checked_add(amount)
+        .ok_or(GameError::ArithmeticOverflow)?;
+    player_state.net_worth = player_state
+        .net_worth
+        .checked_add(amount)
+        .ok_or(GameError::ArithmeticOverflow)?;
+    game.free_parking_pool = 0;
+
+    emit!(FreeParkingCollected {
+        game: game_key,
+        player: player_state.wallet,
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "Player {} collected ${} from Free Parking",
+        player_state.wallet,
+        amount
+    );
+
+    Ok(())
+}
+
+/// Pays the resort bonus for every property the player owns
+pub fn collect_beach_resort_bonus(
+    game_key: Pubkey,
+    player_state: &mut PlayerState,
+    clock: &Sysvar<Clock>,
+) -> Result<()> {
+    let properties_owned = player_state.properties_owned.len() as u8;
+    let amount = properties_owned as u64 * BEACH_RESORT_BONUS_PER_PROPERTY as u64;
+    if amount == 0 {
+        return Ok(());
+    }
+
+    player_state.cash_balance = player_state
+        .cash_balance
+        .checked_add(amount)
+        .ok_or(GameError::ArithmeticOverflow)?;
+    player_state.net_worth = player_state
+        .net_worth
+        .checked_add(amount)
+        .ok_or(GameError::ArithmeticOverflow)?;
+
+    emit!(BeachResortBonusPaid {
+        game: game_key,
+        player: player_state.wallet,
+        properties_owned,
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Free Airdrop Parking doubles as the beach resort: pays the resort bonus
+/// and, when that house rule is on, the jackpot
+pub fn visit_free_parking(
+    game: &mut GameState,
+    game_key: Pubkey,
+    player_state: &mut PlayerState,
+    clock: &Sysvar<Clock>,
+) -> Result<()> {
+    collect_beach_resort_bonus(game_key, player_state, clock)?;
+    collect_free_parking(game, game_key, player_state, clock)
+}
+
+/// Debits `amount` from every other active player and credits it to the
+/// drawer. A player who can't pay is flagged with a debt owed to the drawer,
+/// unless they already owe someone else.
+/// Returns the total collected.
+pub fn collect_from_players<'info>(
+    game: &GameState,
+    game_key: &Pubkey,
+    drawer_state: &mut PlayerState,
+    remaining_accounts: &'info [AccountInfo<'info>],
+    amount: u64,
+) -> Result<u64> {
+    let drawer = drawer_state.wallet;
+    let mut other_players = load_other_player_states(game, game_key, &drawer, remaining_accounts)?;
+    let mut total_collected = 0u64;
+
+    for other_player in other_players.iter_mut() {
+        if other_player.cash_balance >= amount {
+            other_player.cash_balance -= amount;
+            other_player.net_worth = other_player.net_worth.saturating_sub(amount);
+            total_collected = total_collected
+                .checked_add(amount)
+                .ok_or(GameError::ArithmeticOverflow)?;
+        } else if !other_player.can_record_debt(Some(drawer)) {
+            // Already insolvent towards someone else; that debt comes first
+            msg!(
+                "Player {} already owes ${} and pays nothing to {}",
+                other_player.wallet,
+                other_player.debt_amount,
+                drawer
+            );
+            continue;
+        } else {
+            other_player.record_debt(Some(drawer), amount)?;
+            msg!(
+                "Player {} cannot pay ${} to {}. Bankruptcy check required.",
+                other_player.wallet,
+                amount,
+                drawer
+            );
+        }
+
+        other_player.exit(&crate::ID)?;
+    }
+
+    drawer_state.cash_balance = drawer_state
+        .cash_balance
+        .checked_add(total_collected)
+        .ok_or(GameError::ArithmeticOverflow)?;
+    drawer_state.net_worth = drawer_state
+        .net_worth
+        .checked_add(total_collected)
+        .ok_or(GameError::ArithmeticOverflow)?;
+
+    Ok(total_collected)
+}
+
+/// Pays `amount` from the drawer to every other active player. Shares the
+/// drawer can't cover are paid by the bank, which the drawer then owes, so
+/// every player still receives their full share.
+/// Returns the total paid out.
+pub fn pay_each_player<'info>(
+    game: &mut GameState,
+    game_key: &Pubkey,
+    drawer_state: &mut PlayerState,
+    remaining_accounts: &'info [AccountInfo<'info>],
+    amount: u64,
+) -> Result<u64> {
+    let drawer = drawer_state.wallet;
+    let mut other_players = load_other_player_states(game, game_key, &drawer, remaining_accounts)?;
+    let mut total_paid = 0u64;
+    let mut shortfall = 0u64;
+
+    for other_player in other_players.iter_mut() {
+        if drawer_state.cash_balance >= amount {
+            drawer_state.cash_balance -= amount;
+            drawer_state.net_worth = drawer_state.net_worth.saturating_sub(amount);
+        } else {
+            shortfall = shortfall
+                .checked_add(amount)
+                .ok_or(GameError::ArithmeticOverflow)?;
+
+            msg!(
+                "Bank covers ${} owed by {} to {}",
+                amount,
+                drawer,
+                other_player.wallet
+            );
+        }
+
+        other_player.cash_balance = other_player
+            .cash_balance
+            .checked_add(amount)
+            .ok_or(GameError::ArithmeticOverflow)?;
+        other_player.net_worth = other_player
+            .net_worth
+            .checked_add(amount)
+            .ok_or(GameError::ArithmeticOverflow)?;
+        total_paid = total_paid
+            .checked_add(amount)
+            .ok_or(GameError::ArithmeticOverflow)?;
+
+        other_player.exit(&crate::ID)?;
+    }
+
+    if shortfall > 0 {
+        game.bank_balance = game
+            .bank_balance
+            .checked_sub(shortfall)
+            .ok_or(GameError::ArithmeticUnderflow)?;
+        drawer_state.record_debt(None, shortfall)?;
+    }
+
+    Ok(total_paid)
+}
+
 pub fn random_two_u8_with_range(bytes: &[u8; 32], min_value: u8, max_value: u8) -> [u8; 2] {
     let range = (max_value - min_value + 1) as u16;
     let threshold = (256 / range * range) as u8;
@@ -321,19 +637,19 @@ pub fn calculate_rent(
         return Ok(0);
     }
 
-    match static_data.property_type {
+    let rent = match static_data.property_type {
         PropertyType::Street => {
             if property.has_hotel {
-                Ok(static_data.rent[5])
+                static_data.rent[5]
             } else if property.houses > 0 {
-                Ok(static_data.rent[property.houses as usize])
+                static_data.rent[property.houses as usize]
             } else {
                 // Check for monopoly
                 let owner = property.owner.ok_or(GameError::PropertyNotOwned)?;
                 if game.has_monopoly(&owner, static_data.color_group) {
-                    Ok(static_data.rent[0] * 2) // Double rent with monopoly
+                    static_data.rent[0] * 2 // Double rent with monopoly
                 } else {
-                    Ok(static_data.rent[0])
+                    static_data.rent[0]
                 }
             }
         }
@@ -349,13 +665,13 @@ pub fn calculate_rent(
                 })
                 .count();
 
-            Ok(match railroad_count {
+            match railroad_count {
                 1 => 25,
                 2 => 50,
                 3 => 100,
                 4 => 200,
                 _ => 0,
-            })
+            }
         }
         PropertyType::Utility => {
             // Count utilities owned
@@ -372,8 +688,147 @@ pub fn calculate_rent(
             let multiplier = if utility_count == 2 { 10 } else { 4 };
             let dice_total = (dice_roll[0] + dice_roll[1]) as u64;
 
-            Ok(dice_total * multiplier)
+            dice_total * multiplier
         }
-        _ => Ok(0),
+        _ => 0,
+    };
+
+    // Festival boost on the host's chosen street
+    if game.is_festival_property(position) {
+        return rent
+            .checked_mul(FESTIVAL_RENT_MULTIPLIER)
+            .ok_or(GameError::ArithmeticOverflow.into());
+    }
+
+    Ok(rent)
+}
+
+/// Key of an account that may be optional, for use in PDA seeds. Anchor
+/// evaluates seeds both before and after unwrapping optional accounts.
+pub trait SeedKey {
+    fn seed_key(&self) -> Pubkey;
+}
+
+impl<T: Key> SeedKey for Option<Box<T>> {
+    fn seed_key(&self) -> Pubkey {
+        self.as_ref()
+            .map(|account| account.key())
+            .unwrap_or_default()
+    }
+}
+
+impl<T: Key> SeedKey for Box<T> {
+    fn seed_key(&self) -> Pubkey {
+        self.key()
     }
 }
+
+/// Only accepts Token-2022 mints whose extensions the vault is known to
+/// handle: transfer fees, which deposits and payouts account for, and
+/// metadata. Anything else, such as hooks, pausing or a permanent delegate,
+/// could block payouts or drain the vault.
+pub fn validate_mint_extensions(mint: &AccountInfo) -> Result<()> {
+    if *mint.owner != Token2022::id() {
+        return Ok(());
+    }
+
+    let mint_data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+
+    for extension in mint_state.get_extension_types()? {
+        require!(
+            matches!(
+                extension,
+                ExtensionType::TransferFeeConfig
+                    | ExtensionType::MetadataPointer
+                    | ExtensionType::TokenMetadata
+            ),
+            GameError::UnsupportedMintExtension
+        );
+    }
+
+    Ok(())
+}
+
+/// Whether a Token-2022 account still holds transfer fees withheld from deposits
+pub fn has_withheld_transfer_fees(token_account: &AccountInfo) -> Result<bool> {
+    if *token_account.owner != Token2022::id() {
+        return Ok(false);
+    }
+
+    let account_data = token_account.try_borrow_data()?;
+    let account_state =
+        StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account_data)?;
+
+    Ok(account_state
+        .get_extension::<TransferFeeAmount>()
+        .map(|fee_amount| u64::from(fee_amount.withheld_amount) > 0)
+        .unwrap_or(false))
+}
+
+/// Moves the transfer fees withheld in a Token-2022 account to its mint so the
+/// account can be closed. Harvesting needs no authority.
+pub fn harvest_withheld_fees<'info>(
+    token_program: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    token_account: &AccountInfo<'info>,
+) -> Result<()> {
+    let harvest_ctx = CpiContext::new(
+        token_program.clone(),
+        HarvestWithheldTokensToMint {
+            token_program_id: token_program.clone(),
+            mint: mint.clone(),
+        },
+    );
+
+    harvest_withheld_tokens_to_mint(harvest_ctx, vec![token_account.clone()])
+}
+
+/// Deposits lamports into a game's SOL escrow
+pub fn transfer_to_sol_vault<'info>(
+    payer: &AccountInfo<'info>,
+    sol_vault: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let transfer_ctx = CpiContext::new(
+        system_program.clone(),
+        Transfer {
+            from: payer.clone(),
+            to: sol_vault.clone(),
+        },
+    );
+
+    transfer(transfer_ctx, amount)
+}
+
+/// Pays lamports out of a game's SOL escrow, signing for the vault PDA
+pub fn transfer_from_sol_vault<'info>(
+    sol_vault: &AccountInfo<'info>,
+    recipient: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    game_key: &Pubkey,
+    amount: u64,
+) -> Result<()> {
+    let (expected_sol_vault, sol_vault_bump) =
+        Pubkey::find_program_address(&[SOL_VAULT_SEED, game_key.as_ref()], &crate::ID);
+
+    require!(
+        sol_vault.key() == expected_sol_vault,
+        GameError::InvalidSolVault
+    );
+
+    let sol_vault_seeds = &[SOL_VAULT_SEED, game_key.as_ref(), &[sol_vault_bump]];
+    let signer_seeds = &[&sol_vault_seeds[..]];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        system_program.clone(),
+        Transfer {
+            from: sol_vault.clone(),
+            to: recipient.clone(),
+        },
+        signer_seeds,
+    );
+
+    transfer(transfer_ctx, amount)
+}